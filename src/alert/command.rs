@@ -0,0 +1,69 @@
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// How often a running command is checked for exit
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum AlertEvent {
+    Fired,
+    Cleared,
+}
+
+impl AlertEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AlertEvent::Fired => "fired",
+            AlertEvent::Cleared => "cleared",
+        }
+    }
+}
+
+pub struct CommandResult {
+    pub entry_id: u64,
+    pub event: AlertEvent,
+    pub status: String,
+}
+
+fn wait_with_timeout(child: &mut Child, timeout: Duration) -> String {
+    let start = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                return match status.code() {
+                    Some(code) => format!("exit {code}"),
+                    None => "killed".to_string(),
+                }
+            }
+            Ok(None) if start.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return "timeout".to_string();
+            }
+            Ok(None) => thread::sleep(POLL_INTERVAL),
+            Err(err) => return format!("error: {err}"),
+        }
+    }
+}
+
+// Run the command through the shell in a background thread, the exit status is sent back when it's done.
+// Output is discarded, it would break the interface.
+pub fn spawn(command: String, env: Vec<(&'static str, String)>, timeout: Duration,
+             entry_id: u64, event: AlertEvent, sender: Sender<CommandResult>) {
+    thread::spawn(move || {
+        let status = match Command::new("sh")
+            .arg("-c")
+            .arg(&command)
+            .envs(env)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn() {
+            Ok(mut child) => wait_with_timeout(&mut child, timeout),
+            Err(err) => format!("error: {err}"),
+        };
+        let _ = sender.send(CommandResult { entry_id, event, status });
+    });
+}
@@ -0,0 +1,435 @@
+mod command;
+
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Duration;
+
+use chrono::{DateTime, Local, TimeDelta};
+use serde::{Deserialize, Deserializer};
+
+use command::{AlertEvent, CommandResult};
+
+use crate::units::Unit;
+
+// Alert log entries kept, the oldest cleared ones are dropped first
+const ALERT_LOG_CAPACITY: usize = 500;
+
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+pub enum AlertMetric {
+    #[serde(rename = "cpu")]
+    Cpu,
+    #[serde(rename = "mem")]
+    Mem,
+    #[serde(rename = "swap")]
+    Swap,
+    #[serde(rename = "load_1")]
+    Load1,
+    #[serde(rename = "load_5")]
+    Load5,
+    #[serde(rename = "load_15")]
+    Load15,
+}
+
+impl AlertMetric {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AlertMetric::Cpu => "cpu",
+            AlertMetric::Mem => "mem",
+            AlertMetric::Swap => "swap",
+            AlertMetric::Load1 => "load_1",
+            AlertMetric::Load5 => "load_5",
+            AlertMetric::Load15 => "load_15",
+        }
+    }
+
+    pub fn unit(&self) -> Unit {
+        match self {
+            AlertMetric::Cpu | AlertMetric::Mem | AlertMetric::Swap => Unit::Percent,
+            AlertMetric::Load1 | AlertMetric::Load5 | AlertMetric::Load15 => Unit::Plain,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub enum Comparator {
+    #[serde(rename = ">")]
+    Greater,
+    #[serde(rename = ">=")]
+    GreaterOrEqual,
+    #[serde(rename = "<")]
+    Less,
+    #[serde(rename = "<=")]
+    LessOrEqual,
+}
+
+impl Comparator {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Comparator::Greater => ">",
+            Comparator::GreaterOrEqual => ">=",
+            Comparator::Less => "<",
+            Comparator::LessOrEqual => "<=",
+        }
+    }
+
+    fn matches(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparator::Greater => value > threshold,
+            Comparator::GreaterOrEqual => value >= threshold,
+            Comparator::Less => value < threshold,
+            Comparator::LessOrEqual => value <= threshold,
+        }
+    }
+}
+
+// "30s", "5m", "1h" or plain seconds
+pub fn parse_duration(duration: &str) -> Result<Duration, String> {
+    let duration = duration.trim();
+    let (number, multiplier) = match duration.char_indices().last() {
+        Some((i, 's')) => (&duration[..i], 1),
+        Some((i, 'm')) => (&duration[..i], 60),
+        Some((i, 'h')) => (&duration[..i], 3600),
+        _ => (duration, 1),
+    };
+    match number.trim().parse::<u64>() {
+        Ok(number) => Ok(Duration::from_secs(number * multiplier)),
+        Err(_) => Err(format!("invalid duration \"{duration}\", expected e.g. 30s, 5m or 1h")),
+    }
+}
+
+fn deserialize_duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    let duration = String::deserialize(deserializer)?;
+    parse_duration(&duration).map_err(serde::de::Error::custom)
+}
+
+fn default_command_timeout() -> Duration {
+    Duration::from_secs(10)
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AlertRule {
+    pub name: Option<String>,
+    pub metric: AlertMetric,
+    pub comparator: Comparator,
+    pub value: f64,
+    #[serde(rename = "for", default, deserialize_with = "deserialize_duration")]
+    pub duration: Duration,
+
+    // Shell command run when the alert fires or clears
+    pub command: Option<String>,
+    // The command is killed after the timeout
+    #[serde(rename = "timeout", default = "default_command_timeout", deserialize_with = "deserialize_duration")]
+    pub command_timeout: Duration,
+    // Minimal interval between command runs of the rule
+    #[serde(default, deserialize_with = "deserialize_duration")]
+    pub rate_limit: Duration,
+    // Ring the terminal bell when the alert fires
+    #[serde(default)]
+    pub bell: bool,
+}
+
+impl AlertRule {
+    pub fn name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("{} {} {}", self.metric.name(), self.comparator.symbol(), self.value),
+        }
+    }
+
+    pub fn condition(&self) -> String {
+        format!("{} {} {} for {}s", self.metric.name(), self.comparator.symbol(), self.value, self.duration.as_secs())
+    }
+}
+
+pub struct AlertLogEntry {
+    id: u64,
+    pub name: String,
+    pub condition: String,
+    pub value: f64,         // Metric value when the alert fired
+    pub unit: Unit,
+    pub start: DateTime<Local>,
+    pub end: Option<DateTime<Local>>,
+    pub fired_command_status: Option<String>,
+    pub cleared_command_status: Option<String>,
+}
+
+impl AlertLogEntry {
+    // Command exit statuses for the log, empty if the rule has no command
+    pub fn command_status(&self) -> String {
+        match (&self.fired_command_status, &self.cleared_command_status) {
+            (Some(fired), Some(cleared)) => format!("{fired} / {cleared}"),
+            (Some(fired), None) => fired.clone(),
+            (None, Some(cleared)) => format!("- / {cleared}"),
+            (None, None) => "".to_string(),
+        }
+    }
+
+    fn command_status_mut(&mut self, event: AlertEvent) -> &mut Option<String> {
+        match event {
+            AlertEvent::Fired => &mut self.fired_command_status,
+            AlertEvent::Cleared => &mut self.cleared_command_status,
+        }
+    }
+}
+
+// Process with the highest CPU usage, passed to alert commands
+pub struct TopProcess<'a> {
+    pub pid: &'a str,
+    pub name: &'a str,
+    pub cpu: &'a str,
+}
+
+#[derive(Default)]
+struct RuleState {
+    condition_since: Option<DateTime<Local>>,
+    active_entry: Option<u64>,          // Id of the alert log entry
+    last_fired_command_run: Option<DateTime<Local>>,
+    last_cleared_command_run: Option<DateTime<Local>>,
+}
+
+impl RuleState {
+    // Fire and clear commands are rate limited apart, a clear soon after the fire still runs
+    fn last_command_run_mut(&mut self, event: AlertEvent) -> &mut Option<DateTime<Local>> {
+        match event {
+            AlertEvent::Fired => &mut self.last_fired_command_run,
+            AlertEvent::Cleared => &mut self.last_cleared_command_run,
+        }
+    }
+}
+
+pub struct AlertManager {
+    rules: Vec<AlertRule>,
+    states: Vec<RuleState>,
+    pub log: Vec<AlertLogEntry>,
+    next_entry_id: u64,
+    bell: bool,                         // A fired rule wants the terminal bell, rung by the UI
+    command_sender: Sender<CommandResult>,
+    command_receiver: Receiver<CommandResult>,
+}
+
+impl AlertManager {
+    pub fn new(rules: Vec<AlertRule>) -> Self {
+        let states = rules.iter().map(|_| RuleState::default()).collect();
+        let (command_sender, command_receiver) = mpsc::channel();
+        Self {
+            rules,
+            states,
+            log: Vec::new(),
+            next_entry_id: 0,
+            bell: false,
+            command_sender,
+            command_receiver,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn run_command(&self, rule: &AlertRule, state: &mut RuleState, entry: &mut AlertLogEntry,
+                   event: AlertEvent, value: f64, top_process: &Option<TopProcess>, now: DateTime<Local>) {
+
+        let Some(command) = &rule.command else { return };
+
+        let rate_limit = TimeDelta::from_std(rule.rate_limit).unwrap_or(TimeDelta::max_value());
+        let last_command_run = state.last_command_run_mut(event);
+        if last_command_run.is_some_and(|last_run| now - last_run < rate_limit) {
+            *entry.command_status_mut(event) = Some("rate limited".to_string());
+            return;
+        }
+        *last_command_run = Some(now);
+        *entry.command_status_mut(event) = Some("running".to_string());
+
+        let env = vec![
+            ("SLM_ALERT_NAME", entry.name.clone()),
+            ("SLM_ALERT_EVENT", event.name().to_string()),
+            ("SLM_ALERT_METRIC", rule.metric.name().to_string()),
+            ("SLM_ALERT_COMPARATOR", rule.comparator.symbol().to_string()),
+            ("SLM_ALERT_VALUE", value.to_string()),
+            ("SLM_ALERT_THRESHOLD", rule.value.to_string()),
+            ("SLM_ALERT_START", entry.start.to_rfc3339()),
+            ("SLM_ALERT_TOP_PID", top_process.as_ref().map_or("", |process| process.pid).to_string()),
+            ("SLM_ALERT_TOP_NAME", top_process.as_ref().map_or("", |process| process.name).to_string()),
+            ("SLM_ALERT_TOP_CPU", top_process.as_ref().map_or("", |process| process.cpu).to_string()),
+        ];
+        command::spawn(command.clone(), env, rule.command_timeout, entry.id, event, self.command_sender.clone());
+    }
+
+    // Store the exit statuses of finished commands
+    fn collect_command_results(&mut self) {
+        while let Ok(result) = self.command_receiver.try_recv() {
+            if let Some(entry) = self.log.iter_mut().find(|entry| entry.id == result.entry_id) {
+                *entry.command_status_mut(result.event) = Some(result.status);
+            }
+        }
+    }
+
+    // Fire rules whose condition held for their duration, clear rules whose condition is gone
+    pub fn evaluate<F>(&mut self, now: DateTime<Local>, top_process: Option<TopProcess>, metric_value: F)
+    where F: Fn(AlertMetric) -> f64 {
+
+        self.collect_command_results();
+
+        let mut states = std::mem::take(&mut self.states);
+        let mut log = std::mem::take(&mut self.log);
+
+        for (rule, state) in self.rules.iter().zip(states.iter_mut()) {
+            let value = metric_value(rule.metric);
+
+            if !rule.comparator.matches(value, rule.value) {
+                state.condition_since = None;
+                let active_entry = state.active_entry.take()
+                    .and_then(|id| log.iter_mut().find(|entry| entry.id == id));
+                if let Some(entry) = active_entry {
+                    entry.end = Some(now);
+                    self.run_command(rule, state, entry, AlertEvent::Cleared, value, &top_process, now);
+                }
+                continue;
+            }
+
+            let since = *state.condition_since.get_or_insert(now);
+            let sustained = now - since >= TimeDelta::from_std(rule.duration).unwrap_or(TimeDelta::max_value());
+            if sustained && state.active_entry.is_none() {
+                let mut entry = AlertLogEntry {
+                    id: self.next_entry_id,
+                    name: rule.name(),
+                    condition: rule.condition(),
+                    value,
+                    unit: rule.metric.unit(),
+                    start: now,
+                    end: None,
+                    fired_command_status: None,
+                    cleared_command_status: None,
+                };
+                self.next_entry_id += 1;
+                self.run_command(rule, state, &mut entry, AlertEvent::Fired, value, &top_process, now);
+                self.bell |= rule.bell;
+
+                state.active_entry = Some(entry.id);
+                log.push(entry);
+            }
+        }
+
+        self.states = states;
+        self.log = log;
+
+        // Active entries stay until they clear, at most one per rule
+        let mut excess = self.log.len().saturating_sub(ALERT_LOG_CAPACITY);
+        if excess > 0 {
+            self.log.retain(|entry| {
+                let drop = excess > 0 && entry.end.is_some();
+                excess -= drop as usize;
+                !drop
+            });
+        }
+    }
+
+    // Whether a fired rule asked for the bell since the last call, the UI writes it to the terminal
+    pub fn take_bell(&mut self) -> bool {
+        std::mem::take(&mut self.bell)
+    }
+
+    pub fn active(&self) -> impl Iterator<Item = &AlertLogEntry> {
+        self.states.iter()
+            .filter_map(|state| state.active_entry)
+            .filter_map(|id| self.log.iter().find(|entry| entry.id == id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, metric: AlertMetric) -> AlertRule {
+        AlertRule {
+            name: Some(name.to_string()),
+            metric,
+            comparator: Comparator::Greater,
+            value: 50.0,
+            duration: Duration::ZERO,
+            command: None,
+            command_timeout: default_command_timeout(),
+            rate_limit: Duration::ZERO,
+            bell: false,
+        }
+    }
+
+    #[test]
+    fn trimming_the_log_keeps_firing_alerts() {
+        let mut manager = AlertManager::new(vec![rule("cpu", AlertMetric::Cpu), rule("mem", AlertMetric::Mem)]);
+        let start = Local::now();
+
+        // cpu fires once and stays, mem fires and clears until the log overflows
+        for tick in 0..ALERT_LOG_CAPACITY as i64 * 3 {
+            let mem = if tick % 2 == 0 { 90.0 } else { 10.0 };
+            manager.evaluate(start + TimeDelta::seconds(tick), None, |metric| match metric {
+                AlertMetric::Mem => mem,
+                _ => 90.0,
+            });
+        }
+
+        assert!(manager.log.len() <= ALERT_LOG_CAPACITY);
+        assert_eq!(manager.log.iter().filter(|entry| entry.name == "cpu").count(), 1);
+        assert_eq!(manager.active().map(|entry| entry.name.as_str()).collect::<Vec<_>>(), ["cpu"]);
+    }
+
+    #[test]
+    fn clear_command_is_not_rate_limited_by_the_fire_command() {
+        let mut manager = AlertManager::new(vec![AlertRule {
+            command: Some("true".to_string()),
+            rate_limit: Duration::from_secs(3600),
+            ..rule("cpu", AlertMetric::Cpu)
+        }]);
+        let start = Local::now();
+        for (tick, cpu) in [(0, 90.0), (1, 10.0), (2, 90.0)] {
+            manager.evaluate(start + TimeDelta::seconds(tick), None, |_| cpu);
+        }
+
+        let statuses = manager.log.iter()
+            .map(|entry| (entry.fired_command_status.as_deref(), entry.cleared_command_status.as_deref()))
+            .collect::<Vec<_>>();
+        assert_ne!(statuses[0].0, Some("rate limited"));
+        assert_ne!(statuses[0].1, Some("rate limited"));
+        assert_eq!(statuses[1].0, Some("rate limited"));
+    }
+
+    #[test]
+    fn alert_fires_after_the_duration_and_clears() {
+        let mut manager = AlertManager::new(vec![AlertRule {
+            duration: Duration::from_secs(5),
+            bell: true,
+            ..rule("cpu", AlertMetric::Cpu)
+        }]);
+        let start = Local::now();
+        let evaluate = |manager: &mut AlertManager, tick: i64, cpu: f64| {
+            manager.evaluate(start + TimeDelta::seconds(tick), None, |_| cpu)
+        };
+
+        evaluate(&mut manager, 0, 90.0);
+        evaluate(&mut manager, 4, 90.0);
+        assert!(manager.log.is_empty());
+        evaluate(&mut manager, 5, 90.0);
+        evaluate(&mut manager, 6, 90.0);
+        assert_eq!(manager.log.len(), 1);
+        assert!(manager.take_bell());
+        assert!(!manager.take_bell());
+
+        evaluate(&mut manager, 7, 50.0);
+        assert_eq!(manager.log[0].end, Some(start + TimeDelta::seconds(7)));
+        assert_eq!(manager.active().count(), 0);
+    }
+
+    #[test]
+    fn comparators_include_the_threshold_only_when_asked() {
+        assert!(!Comparator::Greater.matches(50.0, 50.0));
+        assert!(Comparator::GreaterOrEqual.matches(50.0, 50.0));
+        assert!(Comparator::Less.matches(49.0, 50.0));
+        assert!(Comparator::LessOrEqual.matches(50.0, 50.0));
+    }
+
+    #[test]
+    fn durations_take_a_unit_suffix() {
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration(" 5m "), Ok(Duration::from_secs(300)));
+        assert_eq!(parse_duration("1h"), Ok(Duration::from_secs(3600)));
+        assert_eq!(parse_duration("10"), Ok(Duration::from_secs(10)));
+        assert!(parse_duration("5d").is_err());
+    }
+}
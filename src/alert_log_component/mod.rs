@@ -0,0 +1,79 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::style::Color::{Black, Cyan};
+use ratatui::widgets::{Block, Cell, HighlightSpacing, Row, StatefulWidget, Table, TableState};
+use crate::alert::AlertLogEntry;
+use crate::units::{Unit, UnitFormat};
+
+pub struct AlertLogComponent<'a> {
+    alert_log: &'a [AlertLogEntry],
+    unit_format: UnitFormat,
+    focused: bool,
+}
+
+impl<'a> AlertLogComponent<'a> {
+    pub fn new(alert_log: &'a [AlertLogEntry], unit_format: UnitFormat) -> Self {
+        Self {
+            alert_log,
+            unit_format,
+            focused: false,
+        }
+    }
+
+    pub fn focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+}
+
+impl StatefulWidget for AlertLogComponent<'_> {
+    type State = TableState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+
+        let header = ["START", "END", "NAME", "CONDITION", "VALUE", "COMMAND"]
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .style(Style::new().fg(Color::Rgb(0, 0, 0)).bg(Color::Green))
+            .height(1);
+
+        // Newest first
+        let rows = self.alert_log.iter().rev().map(|entry| {
+            let (end, style) = match entry.end {
+                Some(end) => (end.format("%m-%d %H:%M:%S").to_string(), Style::new()),
+                None => ("active".to_string(), Style::new().fg(Color::Red).bold()),
+            };
+            Row::new([
+                Cell::from(entry.start.format("%m-%d %H:%M:%S").to_string()),
+                Cell::from(end),
+                Cell::from(entry.name.as_str()),
+                Cell::from(entry.condition.as_str()),
+                Cell::from(self.unit_format.format(entry.unit, entry.value)),
+                Cell::from(entry.command_status()),
+            ])
+                .style(style)
+                .height(1)
+        });
+
+        let t = Table::new(
+            rows,
+            [
+                Constraint::Length(14),
+                Constraint::Length(14),
+                Constraint::Length(20),
+                Constraint::Fill(1),
+                Constraint::Length(self.unit_format.width(Unit::Percent).max(5) as u16),
+                Constraint::Length(24),
+            ],
+        )
+            .header(header)
+            .block(Block::bordered().title(" Alerts ")
+                .border_style(if self.focused { Style::new().fg(Color::Yellow) } else { Style::new() }))
+            .row_highlight_style(Style::new().fg(Black).bg(Cyan))
+            .highlight_spacing(HighlightSpacing::Always);
+
+        t.render(area, buf, state);
+    }
+}
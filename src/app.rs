@@ -1,27 +1,192 @@
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::Instant;
 use chrono::{DateTime, Local};
+use ratatui::layout::{Position, Rect};
 use ratatui::widgets::{ScrollbarState, TableState};
-use sysinfo::{Pid, Process, System, Users};
+use serde::Deserialize;
+use sysinfo::{Disks, Networks, Pid, Process, ProcessStatus, System, ThreadKind, Users};
+use crate::AppResult;
+use crate::alert::{AlertManager, AlertMetric, TopProcess};
+use crate::config::Config;
+use crate::history::{self, History};
+use crate::meminfo::MemoryInfo;
+use crate::keymap::{self, Action, KeyContext};
+use crate::metric_history::{Metric, MetricHistory, LOADED_HISTORY_CAPACITY, METRIC_HISTORY_CAPACITY};
 use crate::proc_table_component::SortTableParam;
 use crate::process_object::ProcessObject;
+use crate::process_object::group::{self, ProcessGroupBy};
+use crate::process_object::history::ProcessHistoryStore;
+use crate::trend_component::{TrendPoints, TrendScale};
+use crate::units::{self, UnitFormat};
+use crate::user_stats::UserStatsStore;
+use crate::user_table_component::UserSortParam;
+use crate::watch_list::WatchList;
 
+// Trends that can be shown in the top right area
+#[derive(Clone, Copy, PartialEq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum TrendKind {
+    Cpu,
+    Mem,
+    Swap,
+    #[serde(rename = "load_1")]
+    #[value(name = "load_1")]
+    Load1,
+    #[serde(rename = "load_5")]
+    #[value(name = "load_5")]
+    Load5,
+    #[serde(rename = "load_15")]
+    #[value(name = "load_15")]
+    Load15,
+    Load,           // 1, 5 and 15 minute load average on one chart
+    Net,            // Network receive and transmit rate
+    Processes,      // Running and other processes, stacked
+    Running,
+}
+
+// Views of the tab bar
+#[derive(Clone, Copy, PartialEq)]
+pub enum View {
+    Overview,
+    Processes,
+    Users,
+    Memory,
+    Network,
+    Disks,
+    History,
+}
+
+impl View {
+    pub const ALL: [View; 7] = [View::Overview, View::Processes, View::Users, View::Memory, View::Network, View::Disks, View::History];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            View::Overview => "Overview",
+            View::Processes => "Processes",
+            View::Users => "Users",
+            View::Memory => "Memory",
+            View::Network => "Network",
+            View::Disks => "Disks",
+            View::History => "History",
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    // Views with the process table, process keys only work there
+    pub fn has_process_table(&self) -> bool {
+        matches!(self, View::Overview | View::Processes)
+    }
+}
+
+// Panels that can be focused and zoomed to the whole data area of a view
+#[derive(Clone, Copy, PartialEq)]
+pub enum Panel {
+    CpuBar,
+    MemBar,
+    SwapBar,
+    Trend(TrendKind),
+    ProcessTrend,       // Selected process CPU and RSS
+    ProcessTable,
+    AlertLog,
+    UserTable,
+    Memory,
+    NetworkTable,
+    DiskTable,
+}
+
+// Focused panel of one view
+#[derive(Clone, Copy)]
+pub struct PanelFocus {
+    pub panel: Panel,
+    pub zoomed: bool,
+}
+
+impl PanelFocus {
+    fn new(view: View) -> Self {
+        let panel = match view {
+            View::Overview | View::Processes => Panel::ProcessTable,
+            View::Users => Panel::UserTable,
+            View::Memory => Panel::Memory,
+            View::Network => Panel::NetworkTable,
+            View::Disks => Panel::DiskTable,
+            View::History => Panel::Trend(TrendKind::Cpu),
+        };
+        Self {
+            panel,
+            zoomed: false,
+        }
+    }
+}
+
+// Traffic of one network interface
+pub struct NetworkInterface {
+    pub name: String,
+    pub rx_rate: f64,       // bytes/s
+    pub tx_rate: f64,       // bytes/s
+    pub total_rx: u64,      // bytes
+    pub total_tx: u64,      // bytes
+}
+
+// User name of a process, empty for kernel threads without a user ID
+fn user_name(users: &Users, process: &Process) -> String {
+    match process.user_id() {
+        None => "".to_string(),
+        Some(user_id) => match users.get_user_by_id(user_id) {
+            None => "root".to_string(),
+            Some(user) => user.name().to_string(),
+        }
+    }
+}
+
+// Points of the metrics a history file recorded
+pub type HistoryPoints = Vec<(Metric, TrendPoints)>;
+
+// Threads share the memory of their process, they are not counted as processes
+fn is_thread(process: &Process) -> bool {
+    process.thread_kind() == Some(ThreadKind::Userland)
+}
+
+// Move the table selection by offset rows within row_count rows
+fn move_table_selection(state: &mut TableState, row_count: usize, offset: isize) {
+    let i = match state.selected() {
+        Some(i) => i.saturating_add_signed(offset).min(row_count.saturating_sub(1)),
+        None => 0,
+    };
+    state.select(Some(i));
+}
 
 pub struct App {
     pub tick_rate: u64,
 
+    // Current view, every view keeps its own state
+    pub view: View,
+
+    // Focus and zoom per view. Panels of the last drawn frame: all of them in focus order,
+    // and the drawn areas for mouse clicks
+    pub panel_focus: [PanelFocus; View::ALL.len()],
+    pub panel_order: Vec<Panel>,
+    pub panel_areas: Vec<(Panel, Rect)>,
+
     pub system_state: System,       // System state
     pub system_uptime: String,      // System uptime
 
-    // Memory
-    pub total_mem: f64,
-    pub usage_mem: f64,
+    // Memory, bytes
+    pub total_mem: u64,
+    pub usage_mem: u64,
     pub total_mem_per: f64,
     pub usage_mem_per: f64,
 
-    // Swap
-    pub total_swap: f64,
-    pub usage_swap: f64,
+    // Used, buffers, cache, free and available memory for the Memory view
+    pub memory: MemoryInfo,
+
+    // Swap, bytes
+    pub total_swap: u64,
+    pub usage_swap: u64,
     pub total_swap_per: f64,
     pub usage_swap_per: f64,
 
@@ -29,9 +194,40 @@ pub struct App {
     cpu_usage_vec: Vec<f32>,
     pub cpu_usage_human: f32,
 
+    // Load average
+    pub load_average_one: f64,
+    pub load_average_five: f64,
+    pub load_average_fifteen: f64,
+
+    // Network rates (bytes/s), over all interfaces and per interface for the Network view
+    networks: Networks,
+    networks_refresh_time: Instant,
+    pub net_rx_rate: f64,
+    pub net_tx_rate: f64,
+    pub network_interfaces: Vec<NetworkInterface>,
+    pub network_table_state: TableState,
+
+    // Mounted disks for the Disks view
+    pub disks: Disks,
+    pub disk_table_state: TableState,
+
+    // Per-user totals for the Users view
+    users: Users,
+    pub user_stats: UserStatsStore,
+    pub user_table_state: TableState,
+    pub user_table_sort_param: UserSortParam,
+
+    // Process count
+    pub process_count: usize,
+    pub running_process_count: usize,
+
     // Trends state
-    pub cpu_usage_trend_vec: Vec<(f64, f64)>,
-    pub mem_usage_trend_vec: Vec<(f64, f64)>,
+    pub trends: Vec<TrendKind>,     // Trends shown in the top right area
+    pub units: UnitFormat,          // Formatting of the shown values
+    pub trend_scale: TrendScale,
+    pub metric_history: MetricHistory,      // Sampled on ticks, trends only read it
+    pub history_loaded: bool,
+    pub history_host_name: Option<String>,     // Host the loaded history was recorded on
 
     // Clock
     pub clock: DateTime<Local>,
@@ -53,41 +249,108 @@ pub struct App {
     pub process_table_sort_active_function: Option<fn(&ProcessObject, &ProcessObject) -> Ordering>,
     pub process_table_sort_param: SortTableParam,
 
+    // Processes pinned to the top of the table, saved to the config file when changed
+    pub watch_list: WatchList,
+    pub config_path: Option<PathBuf>,
+
+    // Grouping by name, cgroup, unit or container, with the expanded group keys
+    pub process_group_by: ProcessGroupBy,
+    pub process_expanded_groups: HashSet<String>,
+
+    // Only the processes of this user, set from the Users view
+    pub process_user_filter: Option<String>,
+
+    // Threads as rows: all of them, or only the threads of one process (PID, name)
+    pub process_show_threads: bool,
+    pub process_thread_view: Option<(Pid, String)>,
+
+    // Per-process CPU and memory history, the selected process trend replaces the last trend
+    pub process_history: ProcessHistoryStore,
+    pub process_trend_visible: bool,
+
+    // Alerts
+    pub alert_manager: AlertManager,
+    pub alert_log_visible: bool,
+    pub alert_log_state: TableState,
+
+    // Help overlay, scrolled by the help_component
+    pub help_visible: bool,
+    pub help_scroll: u16,
+
     // Info string
     pub info_string: String,
 }
 
 impl App {
-    pub fn new(daemon_on: bool, csv_data_file_path: String, tick_rate: u64) -> App {
+    pub fn new(daemon_on: bool, csv_data_file_path: String, tick_rate: u64, config: Config) -> AppResult<App> {
 
-        App {
+        let mut metric_history = MetricHistory::new(if daemon_on { LOADED_HISTORY_CAPACITY } else { METRIC_HISTORY_CAPACITY });
+        let history_host_name = match daemon_on {
+            false => None,
+            true => {
+                let (points, host_name) = App::load_data_from_csv(&csv_data_file_path)?;
+                for (metric, points) in &points {
+                    metric_history.load(*metric, points);
+                }
+                host_name
+            }
+        };
+
+        Ok(App {
             tick_rate,
 
+            view: View::Overview,
+
+            panel_focus: View::ALL.map(PanelFocus::new),
+            panel_order: Vec::new(),
+            panel_areas: Vec::new(),
+
             system_state: System::new(),
             system_uptime: String::new(),
 
-            total_mem: 0.0,
-            usage_mem: 0.0,
+            total_mem: 0,
+            usage_mem: 0,
             total_mem_per: 0.0,
             usage_mem_per: 0.0,
 
-            total_swap: 0.0,
-            usage_swap: 0.0,
+            memory: MemoryInfo::default(),
+
+            total_swap: 0,
+            usage_swap: 0,
             total_swap_per: 0.0,
             usage_swap_per: 0.0,
 
             cpu_usage_vec: vec![0.0, 0.0, 0.0],
             cpu_usage_human: 0.0,
 
-            cpu_usage_trend_vec: match daemon_on {
-                false => Vec::from([(0.0, System::new().global_cpu_usage() as f64)]),
-                true => App::load_data_from_csv(&csv_data_file_path).0,
-            },
+            load_average_one: 0.0,
+            load_average_five: 0.0,
+            load_average_fifteen: 0.0,
+
+            networks: Networks::new_with_refreshed_list(),
+            networks_refresh_time: Instant::now(),
+            net_rx_rate: 0.0,
+            net_tx_rate: 0.0,
+            network_interfaces: Vec::new(),
+            network_table_state: TableState::default().with_selected(0),
 
-            mem_usage_trend_vec: match daemon_on {
-                false => Vec::from([(0.0, System::new().used_memory() as f64)]),
-                true => App::load_data_from_csv(&csv_data_file_path).1,
-            },
+            disks: Disks::new_with_refreshed_list(),
+            disk_table_state: TableState::default().with_selected(0),
+
+            users: Users::new_with_refreshed_list(),
+            user_stats: UserStatsStore::default(),
+            user_table_state: TableState::default().with_selected(0),
+            user_table_sort_param: UserSortParam::Cpu,
+
+            process_count: 0,
+            running_process_count: 0,
+
+            trends: config.trends,
+            units: UnitFormat::new(config.units, config.precision),
+            trend_scale: TrendScale::Fixed,
+            metric_history,
+            history_loaded: daemon_on,
+            history_host_name,
 
             clock: Local::now(),
 
@@ -95,38 +358,89 @@ impl App {
             process_table_state: TableState::default().with_selected(1),
             process_table_scroll_state: ScrollbarState::new(1),
 
-            process_table_sort_by_pid_function: Some(|a: &ProcessObject, b: &ProcessObject| a.pid.parse::<u32>().unwrap().partial_cmp(&b.pid.parse::<u32>().unwrap()).unwrap()),
+            process_table_sort_by_pid_function: Some(|a: &ProcessObject, b: &ProcessObject| a.pid().parse::<u32>().unwrap().partial_cmp(&b.pid().parse::<u32>().unwrap()).unwrap()),
             process_table_sort_by_user_function: Some(|a: &ProcessObject, b: &ProcessObject| a.user().partial_cmp(b.user()).unwrap()),
-            process_table_sort_by_cpu_function: Some(|a: &ProcessObject, b: &ProcessObject| b.cpu.parse::<f32>().unwrap().partial_cmp(&a.cpu.parse::<f32>().unwrap()).unwrap()),
-            process_table_sort_by_mem_function: Some(|a: &ProcessObject, b: &ProcessObject| b.mem.parse::<f64>().unwrap().partial_cmp(&a.mem.parse::<f64>().unwrap()).unwrap()),
+            process_table_sort_by_cpu_function: Some(|a: &ProcessObject, b: &ProcessObject| b.cpu().parse::<f32>().unwrap().partial_cmp(&a.cpu().parse::<f32>().unwrap()).unwrap()),
+            process_table_sort_by_mem_function: Some(|a: &ProcessObject, b: &ProcessObject| b.mem().parse::<f64>().unwrap().partial_cmp(&a.mem().parse::<f64>().unwrap()).unwrap()),
             process_table_sort_by_time_function: Some(|a: &ProcessObject, b: &ProcessObject| a.time_sec.partial_cmp(&b.time_sec).unwrap()),
             process_table_sort_by_name_function: Some(|a: &ProcessObject, b: &ProcessObject| a.name().partial_cmp(b.name()).unwrap()),
             process_table_sort_by_command_function: Some(|a: &ProcessObject, b: &ProcessObject| a.command().partial_cmp(b.command()).unwrap()),
 
-            process_table_sort_active_function: Some(|a: &ProcessObject, b: &ProcessObject| b.cpu.parse::<f32>().unwrap().partial_cmp(&a.cpu.parse::<f32>().unwrap()).unwrap()),
+            process_table_sort_active_function: Some(|a: &ProcessObject, b: &ProcessObject| b.cpu().parse::<f32>().unwrap().partial_cmp(&a.cpu().parse::<f32>().unwrap()).unwrap()),
             process_table_sort_param: crate::proc_table_component::SortTableParam::Cpu,
 
-            info_string: "Down/PageDown/Up/PageUp; Sort: 1 - Pid, 2 - User, 3 - Cpu, 4 - Mem, 5 - Time, 6 - Name, 7 - Command; \
-            F9 - kill selected process; q / F10 - for quit".to_string(),
-        }
-    }
+            watch_list: WatchList::new(config.watch),
+            config_path: config.path,
+
+            process_group_by: ProcessGroupBy::None,
+            process_expanded_groups: HashSet::new(),
+
+            process_user_filter: None,
 
-    pub fn load_data_from_csv(csv_data_file_path: &String) -> (Vec::<(f64, f64)>, Vec::<(f64, f64)>) {
+            process_show_threads: false,
+            process_thread_view: None,
 
-        let mut cpu_data = Vec::<(f64, f64)>::new();
-        let mut mem_data = Vec::<(f64, f64)>::new();
+            process_history: ProcessHistoryStore::default(),
+            process_trend_visible: false,
 
-        let reader = csv::Reader::from_path(csv_data_file_path);
-        for record in reader.unwrap().records() {
-            let record = record.unwrap();
-            let mem_used_in_bytes =  (&record[2].to_string()).parse::<u64>().unwrap();
+            alert_manager: AlertManager::new(config.alert_rules),
+            alert_log_visible: false,
+            alert_log_state: TableState::default(),
+
+            help_visible: false,
+            help_scroll: 0,
+
+            info_string: keymap::hint(&[Action::ShowHelp, Action::NextView, Action::FocusNextPanel, Action::ToggleZoom, Action::Quit]),
+        })
+    }
+
+    pub fn load_data_from_csv(csv_data_file_path: &str) -> AppResult<(HistoryPoints, Option<String>)> {
+        let history = history::load(csv_data_file_path)?;
+        // Legacy recordings don't know the source host memory, use the local one as the best guess
+        let points = App::history_points(&history, || {
             let mut sysinfo = System::new();
-            sysinfo.refresh_all();
-            let mem_used_per = (mem_used_in_bytes as f64 / sysinfo.total_memory() as f64) * 100.0;
-            cpu_data.push(((&record[0].to_string()).parse::<f64>().unwrap(), (&record[1].to_string()).parse::<f64>().unwrap()));
-            mem_data.push(((&record[0].to_string()).parse::<f64>().unwrap(), mem_used_per));
+            sysinfo.refresh_memory();
+            sysinfo.total_memory()
+        });
+        Ok((points, history.host_name().map(str::to_string)))
+    }
+
+    // Trend points of the recorded metrics, columns missing from a record are skipped
+    fn history_points(history: &History, local_mem_total: impl FnOnce() -> u64) -> HistoryPoints {
+        let host_mem_total = history.mem_total().unwrap_or_else(local_mem_total);
+        let percent = |used: u64, total: u64| if total == 0 { 0.0 } else { (used as f64 / total as f64) * 100.0 };
+
+        let mut points = Metric::ALL.map(|metric| (metric, TrendPoints::new()));
+        let mut push = |metric: Metric, time: f64, value: Option<f64>| {
+            if let (Some(value), Some((_, points))) = (value, points.iter_mut().find(|(m, _)| *m == metric)) {
+                points.push((time, value));
+            }
+        };
+        for record in &history.records {
+            let time = record.time;
+            push(Metric::Cpu, time, Some(record.cpu));
+            push(Metric::Mem, time, Some(percent(record.mem_used.unwrap_or(0), record.mem_total.unwrap_or(host_mem_total))));
+            push(Metric::Swap, time, record.swap_used.map(|used| percent(used, record.swap_total.unwrap_or(0))));
+            push(Metric::Load1, time, record.load_average.map(|load| load.0));
+            push(Metric::Load5, time, record.load_average.map(|load| load.1));
+            push(Metric::Load15, time, record.load_average.map(|load| load.2));
+            push(Metric::NetRx, time, record.net_rx);
+            push(Metric::NetTx, time, record.net_tx);
         }
-        (cpu_data, mem_data)
+        points.into_iter().filter(|(_, points)| !points.is_empty()).collect()
+    }
+
+    pub fn set_process_table_sort(&mut self, sort_param: SortTableParam) {
+        self.process_table_sort_active_function = match sort_param {
+            SortTableParam::Pid => self.process_table_sort_by_pid_function,
+            SortTableParam::User => self.process_table_sort_by_user_function,
+            SortTableParam::Cpu => self.process_table_sort_by_cpu_function,
+            SortTableParam::Mem => self.process_table_sort_by_mem_function,
+            SortTableParam::Time => self.process_table_sort_by_time_function,
+            SortTableParam::Name => self.process_table_sort_by_name_function,
+            SortTableParam::Command => self.process_table_sort_by_command_function,
+        };
+        self.process_table_sort_param = sort_param;
     }
 
     pub fn update_state(&mut self) {
@@ -137,46 +451,136 @@ impl App {
         (self.total_mem, self.usage_mem, self.total_mem_per, self.usage_mem_per) =
             self.mem_calc(self.system_state.total_memory(), self.system_state.used_memory());
 
+        self.memory = MemoryInfo::read(&self.system_state);        // Memory breakdown
+
         self.cpu_usage_human = self.usage_calc();                   // Usage calc
 
         // Usage swap
         (self.total_swap, self.usage_swap, self.total_swap_per, self.usage_swap_per) =
             self.mem_calc(self.system_state.total_swap(), self.system_state.used_swap());
 
+        // Load average
+        let load_average = System::load_average();
+        (self.load_average_one, self.load_average_five, self.load_average_fifteen) =
+            (load_average.one, load_average.five, load_average.fifteen);
+
+        // Network rates
+        self.networks.refresh();
+        let elapsed = self.networks_refresh_time.elapsed().as_secs_f64().max(f64::EPSILON);
+        self.networks_refresh_time = Instant::now();
+        self.network_interfaces = self.networks.list().iter()
+            .map(|(name, network)| NetworkInterface {
+                name: name.clone(),
+                rx_rate: network.received() as f64 / elapsed,
+                tx_rate: network.transmitted() as f64 / elapsed,
+                total_rx: network.total_received(),
+                total_tx: network.total_transmitted(),
+            })
+            .collect();
+        self.network_interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+        (self.net_rx_rate, self.net_tx_rate) = self.network_interfaces.iter()
+            .fold((0.0, 0.0), |(rx, tx), interface| (rx + interface.rx_rate, tx + interface.tx_rate));
+
+        // Disks, the list is reread to follow mounts
+        self.disks.refresh_list();
+
         self.clock = self.clock_update();                           // Clock update
 
-        // Process table update
+        // Process table update, the thread view closes when its process exits
         self.system_state.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+        if self.process_thread_view.as_ref().is_some_and(|(pid, _)| self.system_state.process(*pid).is_none()) {
+            self.process_thread_view = None;
+        }
+        self.process_history.update(self.system_state.processes());
+        let processes = self.system_state.processes().values().filter(|process| !is_thread(process));
+        self.process_count = processes.clone().count();
+        self.running_process_count = processes
+            .filter(|process| process.status() == ProcessStatus::Run)
+            .count();
+
+        // User totals
+        self.users.refresh_list();
+        let users = &self.users;
+        self.user_stats.update(self.system_state.processes(), self.system_state.total_memory(), is_thread,
+                               |process| user_name(users, process), self.user_table_sort_param);
+
+        // Trend samples
+        let (process_count, running_process_count) = (self.process_count as f64, self.running_process_count as f64);
+        let (cpu, mem, swap) = (self.cpu_usage_human as f64, self.usage_mem_per, self.usage_swap_per);
+        let (load_one, load_five, load_fifteen) = (self.load_average_one, self.load_average_five, self.load_average_fifteen);
+        let (net_rx, net_tx) = (self.net_rx_rate, self.net_tx_rate);
+        self.metric_history.sample(|metric| match metric {
+            Metric::Cpu => cpu,
+            Metric::Mem => mem,
+            Metric::Swap => swap,
+            Metric::Load1 => load_one,
+            Metric::Load5 => load_five,
+            Metric::Load15 => load_fifteen,
+            Metric::NetRx => net_rx,
+            Metric::NetTx => net_tx,
+            Metric::Processes => process_count,
+            Metric::Running => running_process_count,
+        });
+
         self.process_table_items_vec = self.convert_processes_to_table_items(self.system_state.processes(), self.process_table_sort_active_function);
+        self.clamp_process_table_selection();
+
+        // Alerts, evaluated after the process table update to pass the top process to alert commands
+        let top_process = self.process_table_items_vec.iter()
+            .max_by(|a, b| a.cpu().parse::<f32>().unwrap_or(0.0).total_cmp(&b.cpu().parse::<f32>().unwrap_or(0.0)))
+            .map(|process| TopProcess { pid: process.pid(), name: process.name(), cpu: process.cpu() });
+        self.alert_manager.evaluate(self.clock, top_process, |metric| match metric {
+            AlertMetric::Cpu => cpu,
+            AlertMetric::Mem => mem,
+            AlertMetric::Swap => swap,
+            AlertMetric::Load1 => load_one,
+            AlertMetric::Load5 => load_five,
+            AlertMetric::Load15 => load_fifteen,
+        });
     }
 
-    fn convert_processes_to_table_items<F>(&self, processes: &HashMap<Pid, Process>, sort_fn: Option<F>) -> Vec<ProcessObject>
-    where F: FnMut(&ProcessObject, &ProcessObject) -> Ordering {
+    fn convert_processes_to_table_items(&self, processes: &HashMap<Pid, Process>, sort_fn: Option<fn(&ProcessObject, &ProcessObject) -> Ordering>) -> Vec<ProcessObject> {
+
+        // The main thread and the tasks of the thread view process
+        let thread_view_ids = self.process_thread_view.as_ref().map(|(main_pid, _)| {
+            let mut ids = processes.get(main_pid).and_then(Process::tasks).cloned().unwrap_or_default();
+            ids.insert(*main_pid);
+            ids
+        });
 
         let mut process_object_vec = Vec::<ProcessObject>::new();
-        let users = Users::new_with_refreshed_list();
         for (pid, process) in processes {
+            let thread = is_thread(process);
+            let listed = match &thread_view_ids {
+                Some(ids) => ids.contains(pid),
+                None => self.process_show_threads || !thread,
+            };
+            let user = user_name(&self.users, process);
+            if !listed || self.process_user_filter.as_ref().is_some_and(|filter| *filter != user) {
+                continue;
+            }
             let p_obj = ProcessObject {
                 pid: pid.to_string(),
-                user: match process.user_id() {
-                    None => "".to_string(),
-                    Some(user_id) => match users.get_user_by_id(user_id) {
-                        None => "root".to_string(),
-                        Some(user) => user.name().to_string(),
-                    }
-                },
+                user,
                 cpu: process.cpu_usage().to_string(),
                 mem: ((process.memory() as f64 / self.system_state.total_memory() as f64) * 100.0).to_string(),
+                rss: process.memory(),
                 time_sec: process.run_time(),
-                time: self.proc_time_calc(process.run_time()),
+                time: units::run_time(process.run_time()),
                 name: match process.name().to_str() {
                     Some(name) => name.to_string(),
                     None => "".to_string(),
                 },
-                command: match process.exe() {
-                    Some(exe) => exe.to_str().unwrap().to_string(),
-                    None => "".to_string(),
+                command: match (&thread_view_ids, process.exe()) {
+                    (Some(_), _) => process.status().to_string(),        // State column of the thread view
+                    (None, Some(exe)) => exe.to_str().unwrap().to_string(),
+                    (None, None) => "".to_string(),
                 },
+                pinned: false,
+                cgroup: if self.process_group_by.needs_cgroup() { group::read_cgroup(&pid.to_string()) } else { "".to_string() },
+                group: None,
+                group_member: false,
+                thread,
             };
             process_object_vec.push(p_obj);
         }
@@ -187,13 +591,22 @@ impl App {
                 process_object_vec.sort_by(sort_fn);
             }
         }
-        process_object_vec
+
+        // Pinning applies to the flat table
+        match self.process_group_by {
+            ProcessGroupBy::None => self.watch_list.pin(process_object_vec),
+            group_by => group::group(process_object_vec, group_by, &self.process_expanded_groups, sort_fn),
+        }
     }
 
-    fn mem_calc(&self, total_mem_in_byte: u64, used_mem_in_byte: u64) -> (f64, f64, f64, f64) {
+    // Without memory (no swap) both percents are 0
+    fn mem_calc(&self, total_mem_in_byte: u64, used_mem_in_byte: u64) -> (u64, u64, f64, f64) {
+        if total_mem_in_byte == 0 {
+            return (0, used_mem_in_byte, 0.0, 0.0);
+        }
         (
-            total_mem_in_byte as f64 / (1024.0 * 1024.0 * 1024.0),
-            used_mem_in_byte as f64 / (1024.0 * 1024.0 * 1024.0),
+            total_mem_in_byte,
+            used_mem_in_byte,
             100.0,
             (used_mem_in_byte as f64 / total_mem_in_byte as f64) * 100.0,
         )
@@ -203,8 +616,7 @@ impl App {
         self.cpu_usage_vec.remove(0);
         self.cpu_usage_vec.push(self.system_state.global_cpu_usage());
         let sum: f32 = self.cpu_usage_vec.iter().sum();
-        let avg = sum / self.cpu_usage_vec.len() as f32;
-        avg
+        sum / self.cpu_usage_vec.len() as f32
     }
 
     fn time_to_human_str(&self, system_uptime: u64) -> (u64, u64, u64, u64) {
@@ -223,20 +635,17 @@ impl App {
         format!("{day} days, {hour}:{min}:{sec}")
     }
 
-    fn proc_time_calc(&self, system_uptime: u64) -> String {
-        let (day, hour, min, sec) = self.time_to_human_str(system_uptime);
-        let hour = day * 24 + hour;
-        format!("{hour}:{min}:{sec}")
-    }
-
     fn clock_update(&mut self) -> DateTime<Local>{
         Local::now()
     }
 
     pub fn process_table_next_row(&mut self) {
+        if self.process_table_items_vec.is_empty() {
+            return;
+        }
         let i = match self.process_table_state.selected() {
             Some(i) => {
-                if i >= self.process_table_items_vec.len() - 1 { i } else { i + 1 }
+                if i >= self.process_table_items_vec.len().saturating_sub(1) { i } else { i + 1 }
             }
             None => 0,
         };
@@ -245,6 +654,9 @@ impl App {
     }
 
     pub fn process_table_previous_row(&mut self) {
+        if self.process_table_items_vec.is_empty() {
+            return;
+        }
         let i = match self.process_table_state.selected() {
             Some(i) => {
                 if i == 0 { 0 } else { i - 1 }
@@ -256,10 +668,14 @@ impl App {
     }
 
     pub fn process_table_pagedown_row(&mut self, row_count: usize) {
+        if self.process_table_items_vec.is_empty() {
+            return;
+        }
+        let last_row = self.process_table_items_vec.len().saturating_sub(1);
         let i = match self.process_table_state.selected() {
             Some(i) => {
-                if i >= self.process_table_items_vec.len() - 1 { i }
-                else if i + row_count >= self.process_table_items_vec.len() - 1 { self.process_table_items_vec.len() - 1 }
+                if i >= last_row { i }
+                else if i + row_count >= last_row { last_row }
                 else { i + row_count }
             }
             None => 0,
@@ -269,10 +685,12 @@ impl App {
     }
 
     pub fn process_table_pageup_row(&mut self, row_count: usize) {
+        if self.process_table_items_vec.is_empty() {
+            return;
+        }
         let i = match self.process_table_state.selected() {
             Some(i) => {
-                if i == 0 { 0 }
-                else if i as isize - row_count as isize <= 0 { 0 }
+                if i as isize - row_count as isize <= 0 { 0 }
                 else { i - row_count }
             }
             None => 0,
@@ -281,10 +699,335 @@ impl App {
         self.process_table_scroll_state = self.process_table_scroll_state.position(i);
     }
 
+    pub fn set_view(&mut self, view: View) {
+        self.view = view;
+    }
+
+    // Tab and Shift+Tab cycle the views
+    pub fn next_view(&mut self) {
+        self.view = View::ALL[(self.view.index() + 1) % View::ALL.len()];
+    }
+
+    pub fn previous_view(&mut self) {
+        self.view = View::ALL[(self.view.index() + View::ALL.len() - 1) % View::ALL.len()];
+    }
+
+    // Up / Down move the selection of the focused table, the process table by default
+    pub fn view_next_row(&mut self) {
+        match self.focused_panel() {
+            Panel::AlertLog => self.alert_log_next_row(),
+            Panel::NetworkTable => move_table_selection(&mut self.network_table_state, self.network_interfaces.len(), 1),
+            Panel::DiskTable => move_table_selection(&mut self.disk_table_state, self.disks.list().len(), 1),
+            Panel::UserTable => move_table_selection(&mut self.user_table_state, self.user_stats.users.len(), 1),
+            _ if self.view.has_process_table() => self.process_table_next_row(),
+            _ => {}
+        }
+    }
+
+    pub fn view_previous_row(&mut self) {
+        match self.focused_panel() {
+            Panel::AlertLog => self.alert_log_previous_row(),
+            Panel::NetworkTable => move_table_selection(&mut self.network_table_state, self.network_interfaces.len(), -1),
+            Panel::DiskTable => move_table_selection(&mut self.disk_table_state, self.disks.list().len(), -1),
+            Panel::UserTable => move_table_selection(&mut self.user_table_state, self.user_stats.users.len(), -1),
+            _ if self.view.has_process_table() => self.process_table_previous_row(),
+            _ => {}
+        }
+    }
+
+    // The focused panel of the current view, the first panel if it's gone (alert log closed)
+    pub fn focused_panel(&self) -> Panel {
+        let panel = self.panel_focus[self.view.index()].panel;
+        match self.panel_order.first() {
+            Some(first) if !self.panel_order.contains(&panel) => *first,
+            _ => panel,
+        }
+    }
+
+    pub fn is_zoomed(&self) -> bool {
+        self.panel_focus[self.view.index()].zoomed
+    }
+
+    fn focus_panel(&mut self, panel: Panel) {
+        self.panel_focus[self.view.index()].panel = panel;
+    }
+
+    // Left / Right move the focus, a zoomed view shows the newly focused panel
+    pub fn focus_next_panel(&mut self) {
+        self.move_panel_focus(1);
+    }
+
+    pub fn focus_previous_panel(&mut self) {
+        self.move_panel_focus(self.panel_order.len().saturating_sub(1));
+    }
+
+    fn move_panel_focus(&mut self, offset: usize) {
+        let focused_panel = self.focused_panel();
+        if let Some(i) = self.panel_order.iter().position(|panel| *panel == focused_panel) {
+            self.focus_panel(self.panel_order[(i + offset) % self.panel_order.len()]);
+        }
+    }
+
+    pub fn toggle_zoom(&mut self) {
+        let panel_focus = &mut self.panel_focus[self.view.index()];
+        panel_focus.zoomed = !panel_focus.zoomed;
+    }
+
+    pub fn unzoom(&mut self) {
+        self.panel_focus[self.view.index()].zoomed = false;
+    }
+
+    // A click focuses the panel under the pointer, a click on the focused panel toggles the zoom
+    pub fn click(&mut self, column: u16, row: u16) {
+        let clicked_panel = self.panel_areas.iter()
+            .find(|(_, area)| area.contains(Position::new(column, row)))
+            .map(|(panel, _)| *panel);
+        match clicked_panel {
+            Some(panel) if panel == self.focused_panel() => self.toggle_zoom(),
+            Some(panel) => self.focus_panel(panel),
+            None => {}
+        }
+    }
+
+    // Fixed -> Fit -> Nice
+    pub fn next_trend_scale(&mut self) {
+        self.trend_scale = match self.trend_scale {
+            TrendScale::Fixed => TrendScale::Fit,
+            TrendScale::Fit => TrendScale::Nice,
+            TrendScale::Nice => TrendScale::Fixed,
+        };
+    }
+
+    pub fn toggle_process_trend(&mut self) {
+        self.process_trend_visible = !self.process_trend_visible;
+    }
+
+    // Group summary rows are not processes
+    pub fn selected_process(&self) -> Option<&ProcessObject> {
+        self.process_table_state.selected()
+            .and_then(|i| self.process_table_items_vec.get(i))
+            .filter(|p_obj| !p_obj.is_group())
+    }
+
+    pub fn set_user_table_sort(&mut self, sort_param: UserSortParam) {
+        self.user_table_sort_param = sort_param;
+        self.user_stats.sort(sort_param);
+    }
+
+    // Drill down from the Users view to the processes of the selected user
+    pub fn show_user_processes(&mut self) {
+        let Some(stats) = self.user_table_state.selected().and_then(|i| self.user_stats.users.get(i)) else {
+            return;
+        };
+        self.process_user_filter = Some(stats.user.clone());
+        self.set_view(View::Processes);
+        self.process_table_state.select(Some(0));
+        self.refresh_process_table();
+    }
+
+    pub fn clear_user_filter(&mut self) {
+        if self.process_user_filter.take().is_some() {
+            self.refresh_process_table();
+        }
+    }
+
+    // Threads of the selected process, or back to the process list
+    pub fn toggle_thread_view(&mut self) {
+        if self.process_thread_view.take().is_none() {
+            let Some(selected_item) = self.selected_process() else {
+                return;
+            };
+            let Ok(pid) = selected_item.pid().parse::<u32>() else {
+                return;
+            };
+            self.process_thread_view = Some((Pid::from_u32(pid), selected_item.name().to_string()));
+        }
+        self.process_table_state.select(Some(0));
+        self.refresh_process_table();
+    }
+
+    pub fn toggle_show_threads(&mut self) {
+        self.process_show_threads = !self.process_show_threads;
+        self.refresh_process_table();
+    }
+
+    // User and thread filters of the process table, shown on the tabs
+    pub fn process_table_filter(&self) -> Option<String> {
+        let mut filters = Vec::new();
+        if let Some(user) = &self.process_user_filter {
+            filters.push(user.clone());
+        }
+        match &self.process_thread_view {
+            Some((pid, name)) => filters.push(format!("threads of {name} {pid}")),
+            None if self.process_show_threads => filters.push("threads".to_string()),
+            None => {}
+        }
+        (!filters.is_empty()).then(|| filters.join(", "))
+    }
+
+    pub fn next_process_group_by(&mut self) {
+        self.process_group_by = self.process_group_by.next();
+        self.process_expanded_groups.clear();
+        self.refresh_process_table();
+    }
+
+    // Collapse processes with the same name, or back to the flat table
+    pub fn toggle_name_groups(&mut self) {
+        self.process_group_by = match self.process_group_by {
+            ProcessGroupBy::Name => ProcessGroupBy::None,
+            _ => ProcessGroupBy::Name,
+        };
+        self.process_expanded_groups.clear();
+        self.refresh_process_table();
+    }
+
+    pub fn toggle_selected_group(&mut self) {
+        let Some(selected_item) = self.process_table_state.selected().and_then(|i| self.process_table_items_vec.get(i)) else {
+            return;
+        };
+        if !selected_item.is_group() {
+            return;
+        }
+        let key = selected_item.name().to_string();
+        if !self.process_expanded_groups.remove(&key) {
+            self.process_expanded_groups.insert(key);
+        }
+        self.refresh_process_table();
+    }
+
+    // Rebuild the rows from the last refresh, for changes that should not wait for the next tick
+    fn refresh_process_table(&mut self) {
+        self.process_table_items_vec = self.convert_processes_to_table_items(self.system_state.processes(), self.process_table_sort_active_function);
+        self.clamp_process_table_selection();
+    }
+
+    // Keep the selection on a row, none on an empty table (no threads, all processes of the filter exited)
+    fn clamp_process_table_selection(&mut self) {
+        match self.process_table_items_vec.len().checked_sub(1) {
+            None => self.process_table_state.select(None),
+            Some(last_row) if self.process_table_state.selected().is_some_and(|i| i > last_row) =>
+                self.process_table_state.select(Some(last_row)),
+            Some(_) => {}
+        }
+    }
+
+    // Bindings active now, only the help dialog ones while it's open
+    pub fn key_contexts(&self) -> Vec<KeyContext> {
+        if self.help_visible {
+            return vec![KeyContext::Help];
+        }
+        KeyContext::ALL.into_iter()
+            .filter(|context| match context {
+                KeyContext::Help => false,
+                KeyContext::ProcessTable => self.view.has_process_table(),
+                KeyContext::UserTable => self.view == View::Users,
+                _ => true,
+            })
+            .collect()
+    }
+
+    pub fn show_help(&mut self) {
+        self.help_visible = true;
+        self.help_scroll = 0;
+    }
+
+    pub fn close_help(&mut self) {
+        self.help_visible = false;
+    }
+
+    // Clamped to the help length when rendered
+    pub fn help_scroll_down(&mut self, lines: u16) {
+        self.help_scroll = self.help_scroll.saturating_add(lines);
+    }
+
+    pub fn help_scroll_up(&mut self, lines: u16) {
+        self.help_scroll = self.help_scroll.saturating_sub(lines);
+    }
+
+    pub fn toggle_alert_log(&mut self) {
+        self.alert_log_visible = !self.alert_log_visible;
+    }
+
+    pub fn alert_log_next_row(&mut self) {
+        let i = match self.alert_log_state.selected() {
+            Some(i) => (i + 1).min(self.alert_manager.log.len().saturating_sub(1)),
+            None => 0,
+        };
+        self.alert_log_state.select(Some(i));
+    }
+
+    pub fn alert_log_previous_row(&mut self) {
+        let i = match self.alert_log_state.selected() {
+            Some(i) => i.saturating_sub(1),
+            None => 0,
+        };
+        self.alert_log_state.select(Some(i));
+    }
+
+    pub fn toggle_pin_selected_process(&mut self) {
+        if let Some(selected_item) = self.selected_process() {
+            let name = selected_item.name().to_string();
+            self.watch_list.toggle(&name);
+            if let Some(path) = &self.config_path {
+                if let Err(err) = Config::save_watch(path, self.watch_list.patterns()) {
+                    self.info_string = err.to_string();
+                }
+            }
+            // An unpinned process goes back to its sorted place
+            self.refresh_process_table();
+        }
+    }
+
     pub fn kill_selected_process_from_table(&mut self) {
-        let selected_item_num = self.process_table_state.selected().unwrap();
-        let selected_item_pid = self.process_table_items_vec[selected_item_num].pid.parse::<u32>().unwrap();
-        self.system_state.process(Pid::from_u32(selected_item_pid)).unwrap().kill();
+        let Some(selected_item) = self.process_table_state.selected().and_then(|i| self.process_table_items_vec.get(i)) else {
+            return;
+        };
+        // A signal to a thread goes to its whole process
+        if !selected_item.is_running() || selected_item.is_group() || selected_item.thread {
+            return;
+        }
+        let Ok(selected_item_pid) = selected_item.pid.parse::<u32>() else {
+            return;
+        };
+        // The process may have exited since the last refresh
+        if let Some(process) = self.system_state.process(Pid::from_u32(selected_item_pid)) {
+            process.kill();
+        }
         self.update_state();
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::HistoryRecord;
+
+    fn mem_points(points: &HistoryPoints) -> &[(f64, f64)] {
+        points.iter().find(|(metric, _)| *metric == Metric::Mem).map(|(_, points)| points.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn recorded_memory_usage_is_against_the_recording_host() {
+        // 1000 bytes on the recording host, the second record overrides it with its own column
+        let path = std::env::temp_dir().join(format!("slm-history-test-{}.csv", std::process::id()));
+        std::fs::write(&path, "# slm-history version=2 host=small mem_total=1000\n\
+            time,cpu,mem_used,mem_total\n\
+            1,10,250,\n\
+            2,10,250,2000\n").unwrap();
+        let loaded = App::load_data_from_csv(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        let (points, host_name) = loaded.unwrap();
+        assert_eq!(host_name.as_deref(), Some("small"));
+        assert_eq!(mem_points(&points), [(1.0, 25.0), (2.0, 12.5)]);
+    }
+
+    #[test]
+    fn legacy_memory_usage_is_against_the_local_memory() {
+        let history = History {
+            metadata: HashMap::new(),
+            records: vec![HistoryRecord { time: 1.0, cpu: 10.0, mem_used: Some(100), ..Default::default() }],
+        };
+        assert_eq!(mem_points(&App::history_points(&history, || 400)), [(1.0, 25.0)]);
+    }
+}
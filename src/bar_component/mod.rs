@@ -2,70 +2,182 @@ use ratatui::buffer::Buffer;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use ratatui::prelude::{Color, Style, Widget};
 use ratatui::style::Stylize;
-use ratatui::widgets::{Bar, BarChart, BarGroup, Block, Padding, Paragraph};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Bar, BarChart, BarGroup, Block, LineGauge, Padding, Paragraph};
+use crate::units::{Unit, UnitFormat};
 
-#[derive(Default)]
+// Bar heights are whole numbers, the value is scaled to this many steps of the maximum
+const BAR_RESOLUTION: u64 = 1000;
+// Tick counts tried from the most, all of them split the scale into even parts
+const TICK_COUNTS: [usize; 4] = [5, 3, 2, 1];
+
+// Gauge of a value between 0 and a maximum, with a tick scale and the value in the given unit.
+// A zero maximum (no swap) shows an empty bar and "n/a".
 pub struct BarComponent {
     bar_name: String,
-    bar_name_alignment: Alignment,
-    bar_value: f32,
-    max_bar_value: f64,
-    high_level: f32,
-    high_high_level: f32,
-    text_value: String,
+    value: f64,
+    max_value: f64,
+    high_level: f64,            // % of the maximum
+    high_high_level: f64,       // % of the maximum
+    unit: Unit,
+    unit_format: UnitFormat,
     direction: Direction,
+    compact: bool,
+    focused: bool,
 }
 
 impl BarComponent {
-
-    pub fn new(bar_name:  String, bar_value: f32, max_bar_value: f64,
-               high_level: f32, high_high_level: f32, text_value: String,
-               direction: Direction, bar_name_alignment: Alignment) -> Self {
+    pub fn new(bar_name: String, value: f64, max_value: f64) -> Self {
         Self {
             bar_name,
-            bar_name_alignment,
-            bar_value,
-            max_bar_value,
-            high_level,
-            high_high_level,
-            text_value,
-            direction,
+            value,
+            max_value,
+            high_level: 100.0,
+            high_high_level: 100.0,
+            unit: Unit::Plain,
+            unit_format: UnitFormat::default(),
+            direction: Direction::Vertical,
+            compact: false,
+            focused: false,
         }
     }
 
-    fn bar_chart(&self, bar_value: f32, max_bar_value: u64) -> BarChart<'static> {
+    // Yellow above high, red above high high
+    pub fn levels(mut self, high_level: f64, high_high_level: f64) -> Self {
+        self.high_level = high_level;
+        self.high_high_level = high_high_level;
+        self
+    }
 
-        let bar = Bar::default().value(bar_value as u64)
-            .style(self.bar_style(bar_value, self.high_level, self.high_high_level));
+    pub fn unit(mut self, unit: Unit, unit_format: UnitFormat) -> Self {
+        self.unit = unit;
+        self.unit_format = unit_format;
+        self
+    }
 
-        let bar = if self.direction == Direction::Horizontal {
-            bar.text_value(format!("{}{bar_value}", self.text_value))
-        } else {
-            bar
-        };
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
 
-        let bar_chart = BarChart::default()
-            .value_style(self.bar_style(bar_value, self.high_level, self.high_high_level).reversed())
-            .data(BarGroup::default().bars(&[bar]))
-            .max(max_bar_value)
-            .direction(self.direction);
+    // One line with the name, the value and the bar, without border and scale
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
 
-        if self.direction == Direction::Vertical {
-            bar_chart.bar_width(3)
+    pub fn focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+
+    fn has_max(&self) -> bool {
+        self.max_value.is_finite() && self.max_value > 0.0
+    }
+
+    // Part of the maximum, 0..=1
+    fn ratio(&self) -> f64 {
+        if self.has_max() && self.value.is_finite() {
+            (self.value / self.max_value).clamp(0.0, 1.0)
         } else {
-            bar_chart.bar_width(1)
+            0.0
         }
     }
 
-    fn bar_style(&self, value: f32, high: f32, high_high: f32) -> Style {
-        if value > high_high {
+    fn value_text(&self) -> String {
+        if self.has_max() {
+            self.unit_format.format(self.unit, self.value)
+        } else {
+            "n/a".to_string()
+        }
+    }
+
+    fn bar_style(&self) -> Style {
+        let per = self.ratio() * 100.0;
+        if per > self.high_high_level {
             Style::new().fg(Color::Red)
-        } else if value > high {
+        } else if per > self.high_level {
             Style::new().fg(Color::Yellow)
         } else {
             Style::new().fg(Color::Green)
         }
     }
+
+    // Tick labels from 0 to the maximum, as many as fit when every label gets the room of the widest one
+    fn ticks(&self, space: u16, room: impl Fn(&str) -> u16) -> Vec<(f64, String)> {
+        if !self.has_max() || space == 0 {
+            return Vec::new();
+        }
+        let labels = |count: usize| (0..count)
+            .map(|i| {
+                let fraction = if count == 1 { 1.0 } else { i as f64 / (count - 1) as f64 };
+                (fraction, self.unit_format.format(self.unit, self.max_value * fraction))
+            })
+            .collect::<Vec<_>>();
+        TICK_COUNTS.into_iter()
+            .map(labels)
+            .find(|ticks| {
+                let widest = ticks.iter().map(|(_, label)| room(label)).max().unwrap_or(0);
+                ticks.len() as u16 * widest <= space
+            })
+            .unwrap_or_default()
+    }
+
+    fn bar_chart(&self) -> BarChart<'static> {
+        // The value is shown under the bar, not the scaled bar height
+        let bar = Bar::default()
+            .value((self.ratio() * BAR_RESOLUTION as f64).round() as u64)
+            .text_value(String::new())
+            .style(self.bar_style());
+
+        BarChart::default()
+            .data(BarGroup::default().bars(&[bar]))
+            .max(BAR_RESOLUTION)
+            .direction(self.direction)
+            .bar_width(if self.direction == Direction::Vertical { 3 } else { 1 })
+    }
+
+    // "- label" rows from the bottom (0) to the top (maximum) of the bar
+    fn render_vertical_scale(&self, area: Rect, buf: &mut Buffer) {
+        let last_row = area.height.saturating_sub(1);
+        for (fraction, label) in self.ticks(area.height, |_| 2) {
+            let y = area.bottom() - 1 - (fraction * last_row as f64).round() as u16;
+            Paragraph::new(format!("- {label}")).render(Rect { y, height: 1, ..area }, buf);
+        }
+    }
+
+    // "|" marks over the labels, the first label starts at the left, the last ends at the right
+    fn render_horizontal_scale(&self, area: Rect, buf: &mut Buffer) {
+        let last_column = area.width.saturating_sub(1);
+        let [marks_layout, labels_layout] = Layout::vertical([Constraint::Length(1); 2]).areas(area);
+        for (fraction, label) in self.ticks(area.width, |label| label.len() as u16 + 1) {
+            let x = area.x + (fraction * last_column as f64).round() as u16;
+            let width = label.len() as u16;
+            let label_x = if fraction == 0.0 {
+                x
+            } else if fraction == 1.0 {
+                (x + 1).saturating_sub(width)
+            } else {
+                x.saturating_sub(width / 2)
+            }
+                .clamp(area.x, area.right().saturating_sub(width).max(area.x));
+            Paragraph::new("|").render(Rect { x, width: 1, ..marks_layout }, buf);
+            Paragraph::new(label).render(Rect { x: label_x, width: width.min(area.width), ..labels_layout }, buf);
+        }
+    }
+
+    fn render_compact(&self, area: Rect, buf: &mut Buffer) {
+        let name_style = if self.focused { Style::new().fg(Color::Yellow).bold() } else { Style::new().bold() };
+        LineGauge::default()
+            .label(Line::from(vec![
+                Span::styled(self.bar_name.trim().to_string(), name_style),
+                format!(" {} ", self.value_text()).into(),
+            ]))
+            .ratio(self.ratio())
+            .filled_style(self.bar_style())
+            .unfilled_style(Style::new().fg(Color::DarkGray))
+            .render(area, buf);
+    }
 }
 
 impl Widget for BarComponent {
@@ -73,95 +185,67 @@ impl Widget for BarComponent {
     where
         Self: Sized
     {
-        let outer_block = if self.direction == Direction::Vertical {
-            Block::bordered().title(self.bar_name.clone())
-                .title_alignment(Alignment::Center)
-                .padding(Padding::horizontal(1))
+        if self.compact {
+            self.render_compact(area, buf);
+            return;
+        }
+
+        let outer_block = Block::bordered().title(self.bar_name.clone())
+            .title_alignment(Alignment::Center)
+            .title_bottom(Line::from(format!(" {} ", self.value_text())).centered())
+            .padding(Padding::horizontal(1));
+
+        let outer_block = if self.focused {
+            outer_block.border_style(Style::new().fg(Color::Yellow))
         } else {
-            Block::bordered().title(self.bar_name.clone())
-                .title_alignment(self.bar_name_alignment)
-                .padding(Padding::new(1, 1, 1, 0))
+            outer_block
         };
 
         let inner = outer_block.inner(area);
 
         outer_block.render(area, buf);
 
-        let [bar_layout, grid_layout] = if self.direction == Direction::Vertical {
-            Layout::horizontal([
+        if self.direction == Direction::Vertical {
+            let [bar_layout, scale_layout] = Layout::horizontal([
                 Constraint::Length(4),
-                Constraint::Length(6),
-            ]).areas(inner)
-        } else {
-            Layout::vertical([
-                Constraint::Length(3),
-                Constraint::Length(2),
-            ]).areas(inner)
-        };
-
-        let constraints = Constraint::from_percentages([25; 4]);
-
-        let grid_layouts: [Rect; 4] = if self.direction == Direction::Vertical {
-            Layout::vertical(constraints).areas(grid_layout)
+                Constraint::Fill(1),
+            ]).areas(inner);
+            self.bar_chart().render(bar_layout, buf);
+            self.render_vertical_scale(scale_layout, buf);
         } else {
-            Layout::horizontal(constraints).areas(grid_layout)
-        };
-
-        if self.direction == Direction::Vertical {
-            // 0% position calc
-            let mut n_str: String = String::new();
-            let l = grid_layouts[0].bottom() - grid_layouts[0].top();
-            for _ in 0..l - 1 {
-                n_str.push('\n');
-            }
-
-            let par_0 = Paragraph::new(format!("{n_str}- 0%"));
-            let par_25 = Paragraph::new("- 25%".to_string());
-            let par_50 = Paragraph::new("- 50%".to_string());
-            let par_75 = Paragraph::new("- 75%".to_string());
-            let par_100 = Paragraph::new("- 100%".to_string());
+            // The scale takes two rows under the bar when there is room for it
+            let scale_height = if inner.height >= 3 { 2 } else { 0 };
+            let [bar_layout, scale_layout] = Layout::vertical([
+                Constraint::Fill(1),
+                Constraint::Length(scale_height),
+            ]).areas(inner);
+            self.bar_chart().render(bar_layout, buf);
+            self.render_horizontal_scale(scale_layout, buf);
+        }
+    }
+}
 
-            par_0.render(grid_layouts[3], buf);
-            par_25.render(grid_layouts[3], buf);
-            par_50.render(grid_layouts[2], buf);
-            par_75.render(grid_layouts[1], buf);
-            par_100.render(grid_layouts[0], buf);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        } else {
+    fn tick_labels(bar: &BarComponent, space: u16, room: impl Fn(&str) -> u16) -> Vec<String> {
+        bar.ticks(space, room).into_iter().map(|(_, label)| label).collect()
+    }
 
-            let par_0 = Paragraph::new("|\n0%".to_string()).alignment(Alignment::Left);
-            let par_25 = Paragraph::new("|\n25%".to_string()).alignment(Alignment::Left);
-            let par_50 = Paragraph::new("|\n50%".to_string()).alignment(Alignment::Left);
-            let par_75 = Paragraph::new("|\n75%".to_string()).alignment(Alignment::Left);
-            let par_100 = Paragraph::new("|\n100%".to_string()).alignment(Alignment::Right);
-
-            par_0.render(grid_layouts[0], buf);
-            par_25.render(grid_layouts[1], buf);
-            par_50.render(grid_layouts[2], buf);
-            par_75.render(grid_layouts[3], buf);
-            par_100.render(grid_layouts[3], buf);
-        }
+    #[test]
+    fn ticks_fill_the_space_with_as_many_labels_as_fit() {
+        let bar = BarComponent::new("CPU".to_string(), 10.0, 100.0).unit(Unit::Percent, UnitFormat::default());
+        assert_eq!(tick_labels(&bar, 10, |_| 2), ["0%", "25%", "50%", "75%", "100%"]);
+        assert_eq!(tick_labels(&bar, 9, |_| 2), ["0%", "50%", "100%"]);
+        assert_eq!(tick_labels(&bar, 1, |_| 2), Vec::<String>::new());
+        // Every label gets the room of the widest one
+        assert_eq!(tick_labels(&bar, 10, |label| label.len() as u16 + 1), ["0%", "100%"]);
+    }
 
-        // // 0% position calc
-        // let mut n_str: String = String::new();
-        // let l = grid_layouts[0].bottom() - grid_layouts[0].top();
-        // for _ in 0..l - 1 {
-        //     n_str.push('\n');
-        // }
-        //
-        // let par_0 = Paragraph::new(format!("{n_str}- 0%"));
-        // let par_25 = Paragraph::new(format!("- 25%"));
-        // let par_50 = Paragraph::new(format!("- 50%"));
-        // let par_75 = Paragraph::new(format!("- 75%"));
-        // let par_100 = Paragraph::new(format!("- 100%"));
-        //
-        // par_0.render(grid_layouts[3], buf);
-        // par_25.render(grid_layouts[3], buf);
-        // par_50.render(grid_layouts[2], buf);
-        // par_75.render(grid_layouts[1], buf);
-        // par_100.render(grid_layouts[0], buf);
-
-        let bar = self.bar_chart(self.bar_value, self.max_bar_value as u64);
-        bar.render(bar_layout, buf);
-    }
-}
\ No newline at end of file
+    #[test]
+    fn ticks_of_a_zero_maximum_are_empty() {
+        let bar = BarComponent::new("SWAP".to_string(), 0.0, 0.0);
+        assert!(bar.ticks(100, |_| 1).is_empty());
+    }
+}
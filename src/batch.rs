@@ -0,0 +1,74 @@
+use std::io::Write;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use sysinfo::System;
+
+use crate::app::App;
+use crate::config::Config;
+use crate::proc_table_component::SortTableParam;
+use crate::units::Unit;
+use crate::AppResult;
+
+// Print the header stats and the top rows of the process table, like "top -b"
+fn write_tick(app: &App, rows: usize, out: &mut impl Write) -> AppResult<()> {
+    let load_average = System::load_average();
+
+    let units = app.units;
+    writeln!(out, "{}    up {}    load average: {} {} {}",
+             app.clock.format("%Y-%m-%d %H:%M:%S"), app.system_uptime,
+             units.number(load_average.one), units.number(load_average.five), units.number(load_average.fifteen))?;
+    let bytes = |value: u64| units.format(Unit::Bytes, value as f64);
+    let per = |value: f64| units.format(Unit::Percent, value);
+
+    writeln!(out, "CPU: {}    Memory: {} / {} ({})    Swap: {} / {} ({})",
+             per(app.cpu_usage_human as f64),
+             bytes(app.usage_mem), bytes(app.total_mem), per(app.usage_mem_per),
+             bytes(app.usage_swap), bytes(app.total_swap), per(app.usage_swap_per))?;
+    writeln!(out, "Processes: {}", app.process_table_items_vec.len())?;
+    writeln!(out)?;
+
+    let (per_width, bytes_width) = (units.width(Unit::Plain), units.width(Unit::Bytes));
+    writeln!(out, "{:>7} {:<12} {:>per_width$} {:>per_width$} {:>bytes_width$} {:>12} {:<25} COMMAND",
+             "PID", "USER", "CPU%", "MEM%", "RES", "TIME", "NAME")?;
+    for process in app.process_table_items_vec.iter().take(rows) {
+        writeln!(out, "{:>7} {:<12} {:>per_width$} {:>per_width$} {:>bytes_width$} {:>12} {:<25} {}",
+                 process.pid(), process.user(),
+                 units.number(process.cpu().parse::<f64>().unwrap_or(0.0)),
+                 units.number(process.mem().parse::<f64>().unwrap_or(0.0)),
+                 if process.is_running() { bytes(process.rss) } else { String::new() },
+                 process.time(), process.name(), process.command())?;
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+pub fn run(tick_rate: u64, iterations: Option<u64>, rows: usize, sort_param: SortTableParam,
+           config: Config) -> AppResult<()> {
+    let tick_rate = Duration::from_millis(tick_rate);
+    // Alert rules and their commands belong to the interface, batch output only samples
+    let config = Config { alert_rules: Vec::new(), ..config };
+    let mut app = App::new(false, String::new(), tick_rate.as_millis() as u64, config)?;
+    app.set_process_table_sort(sort_param);
+
+    // CPU usage needs two samples
+    app.update_state();
+    thread::sleep(tick_rate.max(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL));
+
+    let mut stdout = std::io::stdout();
+    let mut iteration = 0;
+    loop {
+        let last_tick = Instant::now();
+
+        app.update_state();
+        write_tick(&app, rows, &mut stdout.lock())?;
+        stdout.flush()?;
+
+        iteration += 1;
+        if iterations.is_some_and(|iterations| iteration >= iterations) {
+            break Ok(());
+        }
+
+        thread::sleep(tick_rate.saturating_sub(last_tick.elapsed()));
+    }
+}
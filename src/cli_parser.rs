@@ -1,4 +1,7 @@
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
+use crate::app::TrendKind;
+use crate::proc_table_component::SortTableParam;
+use crate::units::UnitSystem;
 
 #[derive(Parser)]
 pub struct Cli {
@@ -9,6 +12,62 @@ pub struct Cli {
     #[arg(short = 'p', long = "file-path", default_value = "/tmp/daemon.dat")]
     pub path: std::path::PathBuf,
     /// The tick-rate (ms) for update receive data and interface
-    #[arg(short = 't', long = "tick-rate", default_value = "1000")]
+    #[arg(short = 't', long = "tick-rate", default_value = "1000", global = true)]
     pub tick_rate: u64,
-}
\ No newline at end of file
+    /// The path to the config file (alert rules), "~/.config/slm/config.toml" is used if it exists
+    #[arg(short = 'c', long = "config", global = true)]
+    pub config_path: Option<std::path::PathBuf>,
+    /// Trends shown in the top right area, overrides the config file
+    #[arg(short = 'T', long = "trends", value_enum, value_delimiter = ',')]
+    pub trends: Option<Vec<TrendKind>>,
+    /// Byte units, binary (KiB, MiB, GiB) or SI (kB, MB, GB), overrides the config file
+    #[arg(short = 'u', long = "units", value_enum, global = true)]
+    pub units: Option<UnitSystem>,
+    /// Decimals of the shown values (0-6), overrides the config file
+    #[arg(short = 'P', long = "precision", global = true)]
+    pub precision: Option<usize>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Print a one-shot snapshot of the system state and exit
+    Snapshot {
+        /// Output format
+        #[arg(short = 'f', long = "format", value_enum, default_value = "text")]
+        format: SnapshotFormat,
+        /// The sampling duration (ms), longer duration gives more accurate CPU usage
+        #[arg(short = 'D', long = "duration", default_value = "500")]
+        duration: u64,
+    },
+    /// Print the system state and the process table every tick as plain text
+    Batch {
+        /// Number of iterations, runs until interrupted if not set
+        #[arg(short = 'n', long = "iterations")]
+        iterations: Option<u64>,
+        /// Number of process table rows to print
+        #[arg(short = 'r', long = "rows", default_value = "20")]
+        rows: usize,
+        /// Process table sort column
+        #[arg(short = 's', long = "sort", value_enum, default_value = "cpu")]
+        sort: SortTableParam,
+    },
+    /// Serve Prometheus metrics over HTTP
+    Exporter {
+        /// The address to serve /metrics on
+        #[arg(short = 'l', long = "listen", default_value = "127.0.0.1:9184")]
+        listen: String,
+        /// Number of top processes (by CPU) to export, 0 disables process metrics
+        #[arg(long = "top", default_value = "0")]
+        top_processes: usize,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum SnapshotFormat {
+    Json,
+    Csv,
+    Text,
+}
@@ -0,0 +1,159 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::alert::AlertRule;
+use crate::app::TrendKind;
+use crate::units::{UnitSystem, DEFAULT_PRECISION};
+use crate::AppResult;
+
+// Configuration file (TOML), for example:
+//
+//   # Processes pinned to the top of the process table, by name or glob pattern
+//   watch = ["postgres", "nginx*"]
+//
+//   # Trends shown in the top right area:
+//   # cpu, mem, swap, load_1, load_5, load_15, load, net, processes, running
+//   trends = ["cpu", "mem", "load_1"]
+//
+//   # Byte units: binary (KiB, MiB, GiB) or si (kB, MB, GB), and decimals of the values (0-6)
+//   units = "binary"
+//   precision = 2
+//
+//   [[alert]]
+//   name = "CPU overload"
+//   metric = "cpu"          # cpu, mem, swap, load_1, load_5, load_15
+//   comparator = ">"        # >, >=, <, <=
+//   value = 90
+//   for = "5m"              # sustained duration: 30s, 5m, 1h
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default, rename = "alert")]
+    pub alert_rules: Vec<AlertRule>,
+    #[serde(default)]
+    pub watch: Vec<String>,
+    #[serde(default = "default_trends")]
+    pub trends: Vec<TrendKind>,
+    #[serde(default)]
+    pub units: UnitSystem,
+    #[serde(default = "default_precision")]
+    pub precision: usize,
+    // File the config was read from, or the default one; pins made in the table are saved there
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+fn default_trends() -> Vec<TrendKind> {
+    vec![TrendKind::Cpu, TrendKind::Mem]
+}
+
+fn default_precision() -> usize {
+    DEFAULT_PRECISION
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            alert_rules: Vec::new(),
+            watch: Vec::new(),
+            trends: default_trends(),
+            units: UnitSystem::default(),
+            precision: DEFAULT_PRECISION,
+            path: None,
+        }
+    }
+}
+
+impl Config {
+    pub fn default_path() -> Option<PathBuf> {
+        std::env::var_os("HOME").map(|home| Path::new(&home).join(".config/slm/config.toml"))
+    }
+
+    // Load the given file, or the default one if it exists
+    pub fn load(path: Option<&Path>) -> AppResult<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match Self::default_path() {
+                Some(path) if path.exists() => path,
+                path => return Ok(Config { path, ..Config::default() }),
+            },
+        };
+
+        let content = std::fs::read_to_string(&path)
+            .map_err(|err| format!("can't read config {}: {err}", path.display()))?;
+        let config: Config = toml::from_str(&content)
+            .map_err(|err| format!("invalid config {}: {err}", path.display()))?;
+        Ok(Config { path: Some(path), ..config })
+    }
+
+    // Rewrite the watch key of the config file, the rest of the file (comments, alerts) is kept as is
+    pub fn save_watch(path: &Path, patterns: &[String]) -> AppResult<()> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(format!("can't read config {}: {err}", path.display()).into()),
+        };
+        let watch_line = format!("watch = [{}]", patterns.iter()
+            .map(|pattern| toml::Value::String(pattern.clone()).to_string())
+            .collect::<Vec<_>>()
+            .join(", "));
+        let content = replace_watch(&content, &watch_line);
+
+        // Never write a file that the next start couldn't load
+        toml::from_str::<Config>(&content)
+            .map_err(|err| format!("can't update the watch list of {}: {err}", path.display()))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, content)
+            .map_err(|err| format!("can't write config {}: {err}", path.display()))?;
+        Ok(())
+    }
+}
+
+// Replace the top level watch key, it may span several lines, or add it at the top of the file
+fn replace_watch(content: &str, watch_line: &str) -> String {
+    let lines = content.lines().collect::<Vec<_>>();
+    let top_level = lines.iter().position(|line| line.trim_start().starts_with('[')).unwrap_or(lines.len());
+    let start = lines[..top_level].iter().position(|line| {
+        line.trim_start().strip_prefix("watch").is_some_and(|rest| rest.trim_start().starts_with('='))
+    });
+
+    let mut new_lines = lines.clone();
+    match start {
+        Some(start) => {
+            // The key ends on the first line that makes it a complete value
+            let end = (start..lines.len())
+                .find(|&end| toml::from_str::<toml::Table>(&lines[start..=end].join("\n")).is_ok())
+                .unwrap_or(start);
+            new_lines.splice(start..=end, [watch_line]);
+        }
+        None => new_lines.insert(0, watch_line),
+    }
+    new_lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replace_watch_keeps_the_rest_of_the_file() {
+        let content = "# pinned\nwatch = [\n  \"postgres\",\n  \"nginx*\",\n]\nunits = \"si\"\n\n[[alert]]\nname = \"watch = x\"\n";
+        assert_eq!(
+            replace_watch(content, "watch = [\"postgres\"]"),
+            "# pinned\nwatch = [\"postgres\"]\nunits = \"si\"\n\n[[alert]]\nname = \"watch = x\"\n",
+        );
+    }
+
+    #[test]
+    fn replace_watch_adds_a_missing_key_before_the_tables() {
+        let content = "units = \"si\"\n[[alert]]\nname = \"CPU\"\n";
+        assert_eq!(
+            replace_watch(content, "watch = [\"nginx*\"]"),
+            "watch = [\"nginx*\"]\nunits = \"si\"\n[[alert]]\nname = \"CPU\"\n",
+        );
+        assert_eq!(replace_watch("", "watch = []"), "watch = []\n");
+    }
+}
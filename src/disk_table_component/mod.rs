@@ -0,0 +1,99 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::style::Color::{Black, Cyan};
+use ratatui::widgets::{Block, Cell, HighlightSpacing, Row, StatefulWidget, Table, TableState};
+use sysinfo::Disk;
+use crate::units::{Unit, UnitFormat};
+
+pub struct DiskTableComponent<'a> {
+    disks: &'a [Disk],
+    unit_format: UnitFormat,
+    focused: bool,
+}
+
+impl<'a> DiskTableComponent<'a> {
+    pub fn new(disks: &'a [Disk], unit_format: UnitFormat) -> Self {
+        Self {
+            disks,
+            unit_format,
+            focused: false,
+        }
+    }
+
+    pub fn focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+}
+
+// Same levels as the MEM bar
+fn usage_style(usage_per: f64) -> Style {
+    if usage_per > 90.0 {
+        Style::new().fg(Color::Red)
+    } else if usage_per > 70.0 {
+        Style::new().fg(Color::Yellow)
+    } else {
+        Style::new().fg(Color::Green)
+    }
+}
+
+impl StatefulWidget for DiskTableComponent<'_> {
+    type State = TableState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+
+        let header = ["MOUNT", "DEVICE", "FS", "KIND", "USED", "TOTAL", "USE%"]
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .style(Style::new().fg(Color::Rgb(0, 0, 0)).bg(Color::Green))
+            .height(1);
+
+        let rows = self.disks.iter().map(|disk| {
+            let used = disk.total_space().saturating_sub(disk.available_space());
+            let usage_per = if disk.total_space() == 0 {
+                0.0
+            } else {
+                used as f64 / disk.total_space() as f64 * 100.0
+            };
+            let mut kind = disk.kind().to_string();
+            if disk.is_read_only() {
+                kind.push_str(" ro");
+            }
+            if disk.is_removable() {
+                kind.push_str(" removable");
+            }
+            Row::new([
+                Cell::from(disk.mount_point().display().to_string()),
+                Cell::from(disk.name().to_string_lossy().to_string()),
+                Cell::from(disk.file_system().to_string_lossy().to_string()),
+                Cell::from(kind),
+                Cell::from(self.unit_format.format(Unit::Bytes, used as f64)),
+                Cell::from(self.unit_format.format(Unit::Bytes, disk.total_space() as f64)),
+                Cell::from(self.unit_format.number(usage_per)).style(usage_style(usage_per)),
+            ])
+                .height(1)
+        });
+
+        let t = Table::new(
+            rows,
+            [
+                Constraint::Fill(2),
+                Constraint::Fill(1),
+                Constraint::Length(8),
+                Constraint::Length(16),
+                Constraint::Length(12),
+                Constraint::Length(12),
+                Constraint::Length(6),
+            ],
+        )
+            .header(header)
+            .block(Block::bordered().title(" Disks ")
+                .border_style(if self.focused { Style::new().fg(Color::Yellow) } else { Style::new() }))
+            .row_highlight_style(Style::new().fg(Black).bg(Cyan))
+            .highlight_spacing(HighlightSpacing::Always);
+
+        t.render(area, buf, state);
+    }
+}
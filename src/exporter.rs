@@ -0,0 +1,187 @@
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use sysinfo::System;
+
+use crate::app::App;
+use crate::config::Config;
+use crate::proc_table_component::SortTableParam;
+use crate::snapshot;
+use crate::AppResult;
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+// Clients that don't send the request or read the response in time are dropped
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Label values must escape backslash, double quote and line feed
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn write_header(out: &mut String, name: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} gauge");
+}
+
+// Render the app state in the Prometheus text exposition format
+pub fn render_metrics(app: &App, top_processes: usize) -> String {
+    let system = &app.system_state;
+    let load_average = System::load_average();
+    let mut out = String::new();
+
+    write_header(&mut out, "slm_cpu_usage_percent", "Global CPU usage.");
+    let _ = writeln!(out, "slm_cpu_usage_percent {}", system.global_cpu_usage());
+
+    write_header(&mut out, "slm_cpu_core_usage_percent", "CPU usage per core.");
+    for (core, cpu) in system.cpus().iter().enumerate() {
+        let _ = writeln!(out, "slm_cpu_core_usage_percent{{core=\"{core}\"}} {}", cpu.cpu_usage());
+    }
+
+    write_header(&mut out, "slm_memory_total_bytes", "Total memory.");
+    let _ = writeln!(out, "slm_memory_total_bytes {}", system.total_memory());
+    write_header(&mut out, "slm_memory_used_bytes", "Used memory.");
+    let _ = writeln!(out, "slm_memory_used_bytes {}", system.used_memory());
+
+    write_header(&mut out, "slm_swap_total_bytes", "Total swap.");
+    let _ = writeln!(out, "slm_swap_total_bytes {}", system.total_swap());
+    write_header(&mut out, "slm_swap_used_bytes", "Used swap.");
+    let _ = writeln!(out, "slm_swap_used_bytes {}", system.used_swap());
+
+    write_header(&mut out, "slm_load_average", "System load average.");
+    let _ = writeln!(out, "slm_load_average{{period=\"1m\"}} {}", load_average.one);
+    let _ = writeln!(out, "slm_load_average{{period=\"5m\"}} {}", load_average.five);
+    let _ = writeln!(out, "slm_load_average{{period=\"15m\"}} {}", load_average.fifteen);
+
+    write_header(&mut out, "slm_uptime_seconds", "System uptime.");
+    let _ = writeln!(out, "slm_uptime_seconds {}", System::uptime());
+
+    if top_processes > 0 {
+        let processes = snapshot::top_processes(app).into_iter().take(top_processes);
+
+        write_header(&mut out, "slm_process_cpu_usage_percent", "CPU usage of the top processes.");
+        for process in processes.clone() {
+            let _ = writeln!(out, "slm_process_cpu_usage_percent{{pid=\"{}\",name=\"{}\",user=\"{}\"}} {}",
+                             process.pid(), escape_label(process.name()), escape_label(process.user()),
+                             process.cpu());
+        }
+
+        write_header(&mut out, "slm_process_memory_usage_percent", "Memory usage of the top processes.");
+        for process in processes.clone() {
+            let _ = writeln!(out, "slm_process_memory_usage_percent{{pid=\"{}\",name=\"{}\",user=\"{}\"}} {}",
+                             process.pid(), escape_label(process.name()), escape_label(process.user()),
+                             process.mem());
+        }
+
+        write_header(&mut out, "slm_process_resident_memory_bytes", "Resident memory of the top processes.");
+        for process in processes {
+            let _ = writeln!(out, "slm_process_resident_memory_bytes{{pid=\"{}\",name=\"{}\",user=\"{}\"}} {}",
+                             process.pid(), escape_label(process.name()), escape_label(process.user()),
+                             process.rss);
+        }
+    }
+
+    out
+}
+
+fn handle_connection(mut stream: TcpStream, app: &Mutex<App>, top_processes: usize) -> AppResult<()> {
+    stream.set_read_timeout(Some(CONNECTION_TIMEOUT))?;
+    stream.set_write_timeout(Some(CONNECTION_TIMEOUT))?;
+
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+
+    let mut parts = request_line.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => {
+            let app = app.lock().map_err(|_| "app state lock poisoned")?;
+            ("200 OK", render_metrics(&app, top_processes))
+        },
+        (Some("GET"), _) => ("404 Not Found", "Not found, metrics are served at /metrics\n".to_string()),
+        _ => ("405 Method Not Allowed", "Method not allowed\n".to_string()),
+    };
+
+    write!(stream, "HTTP/1.1 {status}\r\nContent-Type: {CONTENT_TYPE}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+           body.len())?;
+    stream.flush()?;
+    Ok(())
+}
+
+pub fn run(tick_rate: u64, listen: &str, top_processes: usize, config: Config) -> AppResult<()> {
+    let tick_rate = Duration::from_millis(tick_rate);
+    // Alert rules and their commands belong to the interface, the exporter only samples
+    let config = Config { alert_rules: Vec::new(), ..config };
+    let mut app = App::new(false, String::new(), tick_rate.as_millis() as u64, config)?;
+    app.set_process_table_sort(SortTableParam::Cpu);
+    app.update_state();
+    let app = Arc::new(Mutex::new(app));
+
+    // Sample on ticks, requests only read the last state
+    let sampled_app = Arc::clone(&app);
+    thread::spawn(move || loop {
+        thread::sleep(tick_rate.max(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL));
+        match sampled_app.lock() {
+            Ok(mut app) => app.update_state(),
+            Err(_) => break,
+        }
+    });
+
+    let listener = TcpListener::bind(listen)?;
+    eprintln!("Serving metrics at http://{}/metrics", listener.local_addr()?);
+
+    // Every connection on its own thread, a slow client doesn't hold up the scrapes
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let app = Arc::clone(&app);
+                thread::spawn(move || {
+                    if let Err(err) = handle_connection(stream, &app, top_processes) {
+                        eprintln!("Request failed: {err}");
+                    }
+                });
+            }
+            Err(err) => eprintln!("Connection failed: {err}"),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn label_values_are_escaped() {
+        assert_eq!(escape_label("plain"), "plain");
+        assert_eq!(escape_label(r#"a\b"c"#), r#"a\\b\"c"#);
+        assert_eq!(escape_label("two\nlines"), "two\\nlines");
+    }
+
+    #[test]
+    fn every_sample_follows_its_help_and_type() {
+        let mut app = App::new(false, String::new(), 1000, Config::default()).unwrap();
+        app.update_state();
+        let metrics = render_metrics(&app, 3);
+
+        let mut current = None;
+        let mut lines = metrics.lines();
+        while let Some(line) = lines.next() {
+            if let Some(help) = line.strip_prefix("# HELP ") {
+                let name = help.split_once(' ').unwrap().0;
+                assert_eq!(lines.next(), Some(format!("# TYPE {name} gauge").as_str()));
+                current = Some(name);
+            } else {
+                let name = line.split(['{', ' ']).next().unwrap();
+                assert_eq!(Some(name), current, "sample without its header: {line}");
+                assert!(line.rsplit(' ').next().unwrap().parse::<f64>().is_ok(), "not a number: {line}");
+            }
+        }
+        for name in ["slm_cpu_usage_percent", "slm_memory_used_bytes", "slm_load_average",
+                     "slm_process_cpu_usage_percent", "slm_process_resident_memory_bytes"] {
+            assert!(metrics.contains(&format!("# HELP {name} ")), "missing {name}");
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Alignment, Constraint, Flex, Layout, Rect};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Clear, Padding, Paragraph, StatefulWidget, Widget};
+use crate::keymap::{Action, KeyContext, KEYMAP};
+
+// Width of the key column
+const KEY_COLUMN_WIDTH: usize = 28;
+
+// Modal listing every binding of the keymap grouped by context, the state is the scroll offset
+pub struct HelpComponent;
+
+impl HelpComponent {
+    fn lines() -> Vec<Line<'static>> {
+        let mut lines = Vec::new();
+        for context in KeyContext::ALL {
+            if !lines.is_empty() {
+                lines.push(Line::default());
+            }
+            lines.push(Line::from(context.title()).bold().fg(Color::Green));
+            for binding in KEYMAP.iter().filter(|binding| binding.context == context) {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("  {:<KEY_COLUMN_WIDTH$}", binding.key_names()), Style::new().fg(Color::Yellow)),
+                    Span::raw(binding.description),
+                ]));
+            }
+        }
+        lines
+    }
+
+    fn close_hint() -> String {
+        KEYMAP.iter()
+            .find(|binding| binding.action == Action::CloseHelp)
+            .map(|binding| format!(" {} - {} ", binding.key_names(), binding.description))
+            .unwrap_or_default()
+    }
+}
+
+impl StatefulWidget for HelpComponent {
+    type State = u16;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+
+        let lines = Self::lines();
+
+        let [popup] = Layout::horizontal([Constraint::Max(72)]).flex(Flex::Center).areas(area);
+        let [popup] = Layout::vertical([Constraint::Max(lines.len() as u16 + 2)]).flex(Flex::Center).areas(popup);
+
+        let block = Block::bordered()
+            .title(" Help ")
+            .title_bottom(Line::from(Self::close_hint()).alignment(Alignment::Right))
+            .border_style(Style::new().fg(Color::Yellow))
+            .padding(Padding::horizontal(1));
+
+        // Keep the last page in view
+        let max_scroll = (lines.len() as u16).saturating_sub(block.inner(popup).height);
+        *state = (*state).min(max_scroll);
+
+        Clear.render(popup, buf);
+        Paragraph::new(lines)
+            .block(block)
+            .scroll((*state, 0))
+            .render(popup, buf);
+    }
+}
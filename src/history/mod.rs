@@ -0,0 +1,285 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Cursor, Read};
+
+use crate::AppResult;
+
+// History file schema.
+//
+// Version 2 files start with a metadata line followed by a CSV header:
+//
+//   # slm-history version=2 host=<host name> mem_total=<bytes>
+//   time,cpu,mem_used,mem_total,swap_used,swap_total,load_1,load_5,load_15,net_rx,net_tx,disk_read,disk_write,cpu_0,cpu_1,...
+//
+// time       - sample time (seconds)
+// cpu        - global CPU usage (%)
+// cpu_N      - usage of core N (%)
+// mem_*      - memory used / host total memory (bytes)
+// swap_*     - swap used / total (bytes)
+// load_*     - 1, 5 and 15 minute load average
+// net_*      - network receive / transmit rate (bytes/s)
+// disk_*     - disk read / write rate (bytes/s)
+//
+// The metadata line describes the recording host, so that memory usage is
+// computed against the source host and not the machine running the viewer.
+// A "mem_total" column overrides the metadata value per record.
+//
+// Only "time" and "cpu" are required, any other column may be missing and
+// unknown columns are ignored. The viewer doesn't read the per-core and disk
+// columns. Legacy (version 1) files have no metadata and no header, just three
+// positional columns: time, cpu, mem_used. Files of a newer version are rejected.
+
+pub const HISTORY_VERSION: u32 = 2;
+pub const HISTORY_MAGIC: &str = "# slm-history";
+
+pub const METADATA_VERSION: &str = "version";
+pub const METADATA_HOST: &str = "host";
+pub const METADATA_MEM_TOTAL: &str = "mem_total";
+
+pub const COLUMN_TIME: &str = "time";
+pub const COLUMN_CPU: &str = "cpu";
+pub const COLUMN_MEM_USED: &str = "mem_used";
+pub const COLUMN_MEM_TOTAL: &str = "mem_total";
+pub const COLUMN_SWAP_USED: &str = "swap_used";
+pub const COLUMN_SWAP_TOTAL: &str = "swap_total";
+pub const COLUMN_LOAD_1: &str = "load_1";
+pub const COLUMN_LOAD_5: &str = "load_5";
+pub const COLUMN_LOAD_15: &str = "load_15";
+pub const COLUMN_NET_RX: &str = "net_rx";
+pub const COLUMN_NET_TX: &str = "net_tx";
+
+#[derive(Default, Clone)]
+pub struct HistoryRecord {
+    pub time: f64,
+    pub cpu: f64,
+    pub mem_used: Option<u64>,
+    pub mem_total: Option<u64>,
+    pub swap_used: Option<u64>,
+    pub swap_total: Option<u64>,
+    pub load_average: Option<(f64, f64, f64)>,
+    pub net_rx: Option<f64>,
+    pub net_tx: Option<f64>,
+}
+
+pub struct History {
+    pub metadata: HashMap<String, String>,
+    pub records: Vec<HistoryRecord>,
+}
+
+impl History {
+    // Host name of the recording machine
+    pub fn host_name(&self) -> Option<&str> {
+        self.metadata.get(METADATA_HOST).map(String::as_str)
+    }
+
+    // Total memory of the recording machine (bytes)
+    pub fn mem_total(&self) -> Option<u64> {
+        self.metadata.get(METADATA_MEM_TOTAL).and_then(|mem_total| mem_total.parse::<u64>().ok())
+    }
+}
+
+// Column indexes found in the header
+#[derive(Default)]
+struct Columns {
+    time: Option<usize>,
+    cpu: Option<usize>,
+    mem_used: Option<usize>,
+    mem_total: Option<usize>,
+    swap_used: Option<usize>,
+    swap_total: Option<usize>,
+    load_1: Option<usize>,
+    load_5: Option<usize>,
+    load_15: Option<usize>,
+    net_rx: Option<usize>,
+    net_tx: Option<usize>,
+}
+
+impl Columns {
+    fn legacy() -> Self {
+        Self {
+            time: Some(0),
+            cpu: Some(1),
+            mem_used: Some(2),
+            ..Default::default()
+        }
+    }
+
+    fn from_header(header: &csv::StringRecord) -> Self {
+        let mut columns = Columns::default();
+        for (i, name) in header.iter().enumerate() {
+            match name.trim() {
+                COLUMN_TIME => columns.time = Some(i),
+                COLUMN_CPU => columns.cpu = Some(i),
+                COLUMN_MEM_USED => columns.mem_used = Some(i),
+                COLUMN_MEM_TOTAL => columns.mem_total = Some(i),
+                COLUMN_SWAP_USED => columns.swap_used = Some(i),
+                COLUMN_SWAP_TOTAL => columns.swap_total = Some(i),
+                COLUMN_LOAD_1 => columns.load_1 = Some(i),
+                COLUMN_LOAD_5 => columns.load_5 = Some(i),
+                COLUMN_LOAD_15 => columns.load_15 = Some(i),
+                COLUMN_NET_RX => columns.net_rx = Some(i),
+                COLUMN_NET_TX => columns.net_tx = Some(i),
+                // Per-core, disk and unknown columns
+                _ => {}
+            }
+        }
+        columns
+    }
+
+    fn parse_record(&self, record: &csv::StringRecord) -> AppResult<HistoryRecord> {
+        let time = parse_field::<f64>(record, self.time)?.ok_or("history record has no time")?;
+        let cpu = parse_field::<f64>(record, self.cpu)?.ok_or("history record has no cpu usage")?;
+
+        let load_average = match (parse_field::<f64>(record, self.load_1)?,
+                                  parse_field::<f64>(record, self.load_5)?,
+                                  parse_field::<f64>(record, self.load_15)?) {
+            (Some(one), Some(five), Some(fifteen)) => Some((one, five, fifteen)),
+            _ => None,
+        };
+
+        Ok(HistoryRecord {
+            time,
+            cpu,
+            mem_used: parse_field(record, self.mem_used)?,
+            mem_total: parse_field(record, self.mem_total)?,
+            swap_used: parse_field(record, self.swap_used)?,
+            swap_total: parse_field(record, self.swap_total)?,
+            load_average,
+            net_rx: parse_field(record, self.net_rx)?,
+            net_tx: parse_field(record, self.net_tx)?,
+        })
+    }
+}
+
+// Empty and missing fields are None, malformed fields are an error
+fn parse_field<T: std::str::FromStr>(record: &csv::StringRecord, column: Option<usize>) -> AppResult<Option<T>> {
+    match column.and_then(|i| record.get(i)).map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(field) => match field.parse::<T>() {
+            Ok(value) => Ok(Some(value)),
+            Err(_) => Err(format!("invalid value \"{field}\" in history record").into()),
+        },
+    }
+}
+
+// "# slm-history version=2 key=value ..." -> key/value pairs
+fn parse_metadata(line: &str) -> HashMap<String, String> {
+    line.trim_start_matches(HISTORY_MAGIC)
+        .split_whitespace()
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+// Files with a header are version 2 and up, newer versions may change the meaning of the columns
+fn check_version(version: &str) -> AppResult<()> {
+    match version.parse::<u32>() {
+        Ok(version) if (2..=HISTORY_VERSION).contains(&version) => Ok(()),
+        Ok(version) => Err(format!("unsupported history version {version}, this slm reads versions up to {HISTORY_VERSION}").into()),
+        Err(_) => Err(format!("invalid history version \"{version}\"").into()),
+    }
+}
+
+pub fn load(path: &str) -> AppResult<History> {
+    parse(File::open(path)?)
+}
+
+fn parse(reader: impl Read + 'static) -> AppResult<History> {
+    let mut reader = BufReader::new(reader);
+
+    let mut first_line = String::new();
+    reader.read_line(&mut first_line)?;
+
+    let (metadata, has_header, rest): (HashMap<String, String>, bool, Box<dyn Read>) =
+        if first_line.starts_with(HISTORY_MAGIC) {
+            let metadata = parse_metadata(first_line.trim_end());
+            if let Some(version) = metadata.get(METADATA_VERSION) {
+                check_version(version)?;
+            }
+            (metadata, true, Box::new(reader))
+        } else {
+            // Legacy files begin straight with data, a named header without metadata is the current schema
+            let first_field = first_line.split(',').next().unwrap_or("").trim();
+            let legacy = first_field.parse::<f64>().is_ok();
+            (HashMap::new(), !legacy, Box::new(Cursor::new(first_line).chain(reader)))
+        };
+
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .has_headers(has_header)
+        .flexible(true)
+        .from_reader(rest);
+
+    let columns = if has_header {
+        Columns::from_header(csv_reader.headers()?)
+    } else {
+        Columns::legacy()
+    };
+
+    let mut records = Vec::new();
+    for record in csv_reader.records() {
+        records.push(columns.parse_record(&record?)?);
+    }
+
+    Ok(History {
+        metadata,
+        records,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str(content: &'static str) -> History {
+        parse(content.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn current_files_read_the_metadata_and_the_named_columns() {
+        let history = parse_str("# slm-history version=2 host=server01 mem_total=1000\n\
+            time,cpu,mem_used,load_1,load_5,load_15,cpu_0,disk_read,extra\n\
+            1,12.5,400,0.5,0.4,0.3,20,10,x\n");
+
+        assert_eq!(history.host_name(), Some("server01"));
+        assert_eq!(history.mem_total(), Some(1000));
+        let record = &history.records[0];
+        assert_eq!((record.time, record.cpu), (1.0, 12.5));
+        assert_eq!(record.mem_used, Some(400));
+        assert_eq!(record.load_average, Some((0.5, 0.4, 0.3)));
+        assert_eq!(record.swap_used, None);
+    }
+
+    #[test]
+    fn legacy_files_are_positional() {
+        let history = parse_str("1,10,100\n2,20,200\n");
+
+        assert_eq!(history.host_name(), None);
+        assert_eq!(history.records.iter().map(|record| (record.time, record.cpu, record.mem_used)).collect::<Vec<_>>(),
+                   [(1.0, 10.0, Some(100)), (2.0, 20.0, Some(200))]);
+    }
+
+    #[test]
+    fn a_header_without_metadata_is_the_current_schema() {
+        let history = parse_str("time,cpu,net_rx\n1,10,\n");
+
+        assert_eq!(history.records[0].cpu, 10.0);
+        assert_eq!(history.records[0].net_rx, None);
+    }
+
+    #[test]
+    fn newer_and_malformed_versions_are_rejected() {
+        let error = |content: &'static str| parse(content.as_bytes()).err().map(|err| err.to_string());
+
+        assert_eq!(error("# slm-history version=3\ntime,cpu\n1,10\n").as_deref(),
+                   Some("unsupported history version 3, this slm reads versions up to 2"));
+        assert_eq!(error("# slm-history version=two\ntime,cpu\n1,10\n").as_deref(),
+                   Some("invalid history version \"two\""));
+        assert!(error("# slm-history version=2\ntime,cpu\n1,10\n").is_none());
+    }
+
+    #[test]
+    fn malformed_and_incomplete_records_are_errors() {
+        assert!(parse("time,cpu\n1,busy\n".as_bytes()).is_err());
+        assert!(parse("time,mem_used\n1,100\n".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,213 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::app::View;
+use crate::proc_table_component::SortTableParam;
+use crate::user_table_component::UserSortParam;
+
+// Everything a key can do, handle_events runs the action
+#[derive(Clone, Copy, PartialEq)]
+pub enum Action {
+    Quit,
+    ShowHelp,
+    CloseHelp,
+    HelpScrollDown,
+    HelpScrollUp,
+    HelpPageDown,
+    HelpPageUp,
+    NextView,
+    PreviousView,
+    SetView(View),
+    FocusNextPanel,
+    FocusPreviousPanel,
+    ToggleZoom,
+    Unzoom,
+    NextRow,
+    PreviousRow,
+    PageDown,
+    PageUp,
+    Sort(SortTableParam),
+    KillProcess,
+    TogglePin,
+    ToggleProcessTrend,
+    ToggleThreadView,
+    ToggleShowThreads,
+    NextProcessGroupBy,
+    ToggleNameGroups,
+    ToggleGroup,
+    ClearUserFilter,
+    SortUsers(UserSortParam),
+    ShowUserProcesses,
+    NextTrendScale,
+    ToggleAlertLog,
+    AlertLogNextRow,
+    AlertLogPreviousRow,
+}
+
+// Where a binding works, also the groups of the help overlay
+#[derive(Clone, Copy, PartialEq)]
+pub enum KeyContext {
+    Global,
+    Views,
+    Panels,
+    ProcessTable,       // Views with the process table
+    UserTable,
+    Trends,
+    Alerts,
+    Help,               // Help dialog, the other bindings are off while it's open
+}
+
+impl KeyContext {
+    pub const ALL: [KeyContext; 8] = [
+        KeyContext::Global, KeyContext::Views, KeyContext::Panels, KeyContext::ProcessTable,
+        KeyContext::UserTable, KeyContext::Trends, KeyContext::Alerts, KeyContext::Help,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            KeyContext::Global => "Global",
+            KeyContext::Views => "Views",
+            KeyContext::Panels => "Panels",
+            KeyContext::ProcessTable => "Process table",
+            KeyContext::UserTable => "Users",
+            KeyContext::Trends => "Trends",
+            KeyContext::Alerts => "Alerts",
+            KeyContext::Help => "Help dialog",
+        }
+    }
+}
+
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    const fn new(code: KeyCode) -> Self {
+        Self {
+            code,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    const fn char(c: char) -> Self {
+        Self::new(KeyCode::Char(c))
+    }
+
+    // Shift is ignored, terminals differ in reporting it for "?" or Shift+Tab
+    fn matches(&self, key: &KeyEvent) -> bool {
+        self.code == key.code && self.modifiers == key.modifiers.difference(KeyModifiers::SHIFT)
+    }
+
+    pub fn name(&self) -> String {
+        match self.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::F(n) => format!("F{n}"),
+            KeyCode::BackTab => "Shift+Tab".to_string(),
+            KeyCode::PageDown => "PageDown".to_string(),
+            KeyCode::PageUp => "PageUp".to_string(),
+            code => format!("{code:?}"),
+        }
+    }
+}
+
+pub struct KeyBinding {
+    pub context: KeyContext,
+    pub keys: &'static [Key],
+    pub action: Action,
+    pub description: &'static str,
+}
+
+impl KeyBinding {
+    pub fn key_names(&self) -> String {
+        self.keys.iter().map(Key::name).collect::<Vec<_>>().join(" / ")
+    }
+}
+
+const fn bind(context: KeyContext, keys: &'static [Key], action: Action, description: &'static str) -> KeyBinding {
+    KeyBinding {
+        context,
+        keys,
+        action,
+        description,
+    }
+}
+
+// The one source of key bindings, for handle_events, the help overlay and the bottom hint
+pub const KEYMAP: &[KeyBinding] = &[
+    bind(KeyContext::Help, &[Key::new(KeyCode::Esc), Key::char('?'), Key::new(KeyCode::F(1)), Key::char('q')],
+         Action::CloseHelp, "close help"),
+    bind(KeyContext::Help, &[Key::new(KeyCode::Down)], Action::HelpScrollDown, "scroll down"),
+    bind(KeyContext::Help, &[Key::new(KeyCode::Up)], Action::HelpScrollUp, "scroll up"),
+    bind(KeyContext::Help, &[Key::new(KeyCode::PageDown)], Action::HelpPageDown, "page down"),
+    bind(KeyContext::Help, &[Key::new(KeyCode::PageUp)], Action::HelpPageUp, "page up"),
+
+    bind(KeyContext::Global, &[Key::char('?'), Key::new(KeyCode::F(1))], Action::ShowHelp, "help"),
+    bind(KeyContext::Global, &[Key::char('q'), Key::new(KeyCode::F(10))], Action::Quit, "quit"),
+
+    bind(KeyContext::Views, &[Key::new(KeyCode::Tab)], Action::NextView, "next view"),
+    bind(KeyContext::Views, &[Key::new(KeyCode::BackTab)], Action::PreviousView, "previous view"),
+    bind(KeyContext::Views, &[Key::new(KeyCode::F(2))], Action::SetView(View::Overview), "Overview"),
+    bind(KeyContext::Views, &[Key::new(KeyCode::F(3))], Action::SetView(View::Processes), "Processes"),
+    bind(KeyContext::Views, &[Key::new(KeyCode::F(4))], Action::SetView(View::Users), "Users"),
+    bind(KeyContext::Views, &[Key::new(KeyCode::F(5))], Action::SetView(View::Memory), "Memory"),
+    bind(KeyContext::Views, &[Key::new(KeyCode::F(6))], Action::SetView(View::Network), "Network"),
+    bind(KeyContext::Views, &[Key::new(KeyCode::F(7))], Action::SetView(View::Disks), "Disks"),
+    bind(KeyContext::Views, &[Key::new(KeyCode::F(8))], Action::SetView(View::History), "History"),
+
+    bind(KeyContext::Panels, &[Key::new(KeyCode::Right)], Action::FocusNextPanel, "focus next panel"),
+    bind(KeyContext::Panels, &[Key::new(KeyCode::Left)], Action::FocusPreviousPanel, "focus previous panel"),
+    bind(KeyContext::Panels, &[Key::char('z')], Action::ToggleZoom, "zoom / restore the focused panel"),
+    bind(KeyContext::Panels, &[Key::new(KeyCode::Esc)], Action::Unzoom, "restore the zoomed panel"),
+    bind(KeyContext::Panels, &[Key::new(KeyCode::Down)], Action::NextRow, "next row of the focused table"),
+    bind(KeyContext::Panels, &[Key::new(KeyCode::Up)], Action::PreviousRow, "previous row of the focused table"),
+
+    bind(KeyContext::ProcessTable, &[Key::new(KeyCode::PageDown)], Action::PageDown, "page down"),
+    bind(KeyContext::ProcessTable, &[Key::new(KeyCode::PageUp)], Action::PageUp, "page up"),
+    bind(KeyContext::ProcessTable, &[Key::char('1')], Action::Sort(SortTableParam::Pid), "sort by PID"),
+    bind(KeyContext::ProcessTable, &[Key::char('2')], Action::Sort(SortTableParam::User), "sort by user"),
+    bind(KeyContext::ProcessTable, &[Key::char('3')], Action::Sort(SortTableParam::Cpu), "sort by CPU"),
+    bind(KeyContext::ProcessTable, &[Key::char('4')], Action::Sort(SortTableParam::Mem), "sort by memory"),
+    bind(KeyContext::ProcessTable, &[Key::char('5')], Action::Sort(SortTableParam::Time), "sort by time"),
+    bind(KeyContext::ProcessTable, &[Key::char('6')], Action::Sort(SortTableParam::Name), "sort by name"),
+    bind(KeyContext::ProcessTable, &[Key::char('7')], Action::Sort(SortTableParam::Command), "sort by command"),
+    bind(KeyContext::ProcessTable, &[Key::new(KeyCode::F(9))], Action::KillProcess, "kill the selected process"),
+    bind(KeyContext::ProcessTable, &[Key::char('p')], Action::TogglePin, "pin / unpin the selected process"),
+    bind(KeyContext::ProcessTable, &[Key::char('h')], Action::ToggleProcessTrend, "selected process trend"),
+    bind(KeyContext::ProcessTable, &[Key::char('t')], Action::ToggleThreadView, "threads of the selected process / back"),
+    bind(KeyContext::ProcessTable, &[Key::char('T')], Action::ToggleShowThreads, "list all threads as rows"),
+    bind(KeyContext::ProcessTable, &[Key::char('g')], Action::NextProcessGroupBy, "group by: cgroup, systemd unit, container, off"),
+    bind(KeyContext::ProcessTable, &[Key::char('c')], Action::ToggleNameGroups, "collapse processes with the same name"),
+    bind(KeyContext::ProcessTable, &[Key::new(KeyCode::Enter)], Action::ToggleGroup, "expand / collapse the selected group"),
+    bind(KeyContext::ProcessTable, &[Key::new(KeyCode::Backspace)], Action::ClearUserFilter, "show the processes of all users"),
+
+    bind(KeyContext::UserTable, &[Key::char('1')], Action::SortUsers(UserSortParam::User), "sort by user"),
+    bind(KeyContext::UserTable, &[Key::char('2')], Action::SortUsers(UserSortParam::Processes), "sort by process count"),
+    bind(KeyContext::UserTable, &[Key::char('3')], Action::SortUsers(UserSortParam::Cpu), "sort by CPU"),
+    bind(KeyContext::UserTable, &[Key::char('4')], Action::SortUsers(UserSortParam::Mem), "sort by memory"),
+    bind(KeyContext::UserTable, &[Key::char('5')], Action::SortUsers(UserSortParam::Io), "sort by disk I/O"),
+    bind(KeyContext::UserTable, &[Key::new(KeyCode::Enter)], Action::ShowUserProcesses, "processes of the selected user"),
+
+    bind(KeyContext::Trends, &[Key::char('y')], Action::NextTrendScale, "trend scale: fixed, fit, nice"),
+
+    bind(KeyContext::Alerts, &[Key::char('a')], Action::ToggleAlertLog, "show / hide the alert log"),
+    bind(KeyContext::Alerts, &[Key::char(']')], Action::AlertLogNextRow, "next alert"),
+    bind(KeyContext::Alerts, &[Key::char('[')], Action::AlertLogPreviousRow, "previous alert"),
+];
+
+// Action of the first binding of an active context matching the key
+pub fn action(key: &KeyEvent, contexts: &[KeyContext]) -> Option<Action> {
+    KEYMAP.iter()
+        .filter(|binding| contexts.contains(&binding.context))
+        .find(|binding| binding.keys.iter().any(|binding_key| binding_key.matches(key)))
+        .map(|binding| binding.action)
+}
+
+// Short key list for the bottom line
+pub fn hint(actions: &[Action]) -> String {
+    actions.iter()
+        .filter_map(|action| KEYMAP.iter().find(|binding| binding.action == *action && binding.context != KeyContext::Help))
+        .map(|binding| format!("{} - {}", binding.key_names(), binding.description))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
@@ -6,29 +6,70 @@ mod app;
 mod ui;
 mod process_object;
 mod cli_parser;
+mod config;
+mod alert;
+mod alert_log_component;
+mod network_table_component;
+mod disk_table_component;
+mod user_table_component;
+mod user_stats;
+mod help_component;
+mod keymap;
+mod watch_list;
+mod history;
+mod metric_history;
+mod meminfo;
+mod memory_component;
+mod snapshot;
+mod batch;
+mod exporter;
+mod units;
 
 use std::error;
+use std::io::Write;
 use std::time::{Duration, Instant};
 use clap::Parser;
 use crossterm::event;
-use crossterm::event::{Event, KeyCode, KeyEventKind};
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind, MouseButton, MouseEventKind};
+use crossterm::execute;
 use crate::app::App;
-use crate::proc_table_component::SortTableParam;
+use crate::keymap::Action;
 pub type AppResult<T> = std::result::Result<T, Box<dyn error::Error>>;
 
-use cli_parser::Cli;
+use cli_parser::{Cli, Command};
+use config::Config;
 
-fn main() -> std::io::Result<()> {
+fn main() -> AppResult<()> {
 
     let args = Cli::parse();
 
-    let mut terminal = ratatui::init();
+    let mut config = Config::load(args.config_path.as_deref())?;
+    if let Some(trends) = args.trends {
+        config.trends = trends;
+    }
+    if let Some(units) = args.units {
+        config.units = units;
+    }
+    if let Some(precision) = args.precision {
+        config.precision = precision;
+    }
+    match args.command {
+        Some(Command::Snapshot { format, duration }) => return snapshot::run(format, duration, config),
+        Some(Command::Batch { iterations, rows, sort }) => return batch::run(args.tick_rate, iterations, rows, sort, config),
+        Some(Command::Exporter { listen, top_processes }) => return exporter::run(args.tick_rate, &listen, top_processes, config),
+        None => {}
+    }
+
+    let mut app = App::new(args.daemon_on, args.path.display().to_string(), args.tick_rate, config)?;
 
-    let mut app = App::new(args.daemon_on, args.path.display().to_string(), args.tick_rate);
+    // Mouse clicks focus and zoom panels
+    let mut terminal = ratatui::init();
+    execute!(std::io::stdout(), EnableMouseCapture)?;
     let result = run(&mut terminal, &mut app);
+    execute!(std::io::stdout(), DisableMouseCapture)?;
     ratatui::restore();
 
-    result
+    Ok(result?)
 }
 
 fn run(terminal: &mut ratatui::DefaultTerminal, app: &mut App) -> std::io::Result<()> {
@@ -38,12 +79,14 @@ fn run(terminal: &mut ratatui::DefaultTerminal, app: &mut App) -> std::io::Resul
 
     loop {
         terminal.draw(|frame| ui::draw(frame, app))?;
+        if app.alert_manager.take_bell() {
+            terminal.backend_mut().write_all(b"\x07")?;
+            terminal.backend_mut().flush()?;
+        }
 
         let timeout = tick_rate.saturating_sub(last_tick.elapsed());
-        if event::poll(timeout)? {
-            if handle_events(app)? {
-                break Ok(());
-            }
+        if event::poll(timeout)? && handle_events(app)? {
+            break Ok(());
         }
 
         if last_tick.elapsed() >= tick_rate {
@@ -55,27 +98,51 @@ fn run(terminal: &mut ratatui::DefaultTerminal, app: &mut App) -> std::io::Resul
 
 fn handle_events(app: &mut App) -> std::io::Result<bool> {
     match event::read()? {
-        Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
-            KeyCode::Char('q') => return Ok(true),
-            KeyCode::F(10) => return Ok(true),
-            KeyCode::Down => app.process_table_next_row(),
-            KeyCode::Up => app.process_table_previous_row(),
-            KeyCode::PageDown => app.process_table_pagedown_row(20),
-            KeyCode::PageUp => app.process_table_pageup_row(20),
-            KeyCode::F(9) => app.kill_selected_process_from_table(),
-
-            KeyCode::Char('1') => {app.process_table_sort_active_function = app.process_table_sort_by_pid_function; app.process_table_sort_param = SortTableParam::Pid},
-            KeyCode::Char('2') => { app.process_table_sort_active_function = app.process_table_sort_by_user_function; app.process_table_sort_param = SortTableParam::User},
-            KeyCode::Char('3') => { app.process_table_sort_active_function = app.process_table_sort_by_cpu_function; app.process_table_sort_param = SortTableParam::Cpu},
-            KeyCode::Char('4') => { app.process_table_sort_active_function = app.process_table_sort_by_mem_function; app.process_table_sort_param = SortTableParam::Mem},
-            KeyCode::Char('5') => { app.process_table_sort_active_function = app.process_table_sort_by_time_function; app.process_table_sort_param = SortTableParam::Time},
-            KeyCode::Char('6') => { app.process_table_sort_active_function = app.process_table_sort_by_name_function; app.process_table_sort_param = SortTableParam::Name},
-            KeyCode::Char('7') => { app.process_table_sort_active_function = app.process_table_sort_by_command_function; app.process_table_sort_param = SortTableParam::Command},
-
-            // handle other key events
-            _ => {}
-        },
+        Event::Key(key) if key.kind == KeyEventKind::Press => {
+            let Some(action) = keymap::action(&key, &app.key_contexts()) else {
+                return Ok(false);
+            };
+            match action {
+                Action::Quit => return Ok(true),
+                Action::ShowHelp => app.show_help(),
+                Action::CloseHelp => app.close_help(),
+                Action::HelpScrollDown => app.help_scroll_down(1),
+                Action::HelpScrollUp => app.help_scroll_up(1),
+                Action::HelpPageDown => app.help_scroll_down(10),
+                Action::HelpPageUp => app.help_scroll_up(10),
+                Action::NextView => app.next_view(),
+                Action::PreviousView => app.previous_view(),
+                Action::SetView(view) => app.set_view(view),
+                Action::FocusNextPanel => app.focus_next_panel(),
+                Action::FocusPreviousPanel => app.focus_previous_panel(),
+                Action::ToggleZoom => app.toggle_zoom(),
+                Action::Unzoom => app.unzoom(),
+                Action::NextRow => app.view_next_row(),
+                Action::PreviousRow => app.view_previous_row(),
+                Action::PageDown => app.process_table_pagedown_row(20),
+                Action::PageUp => app.process_table_pageup_row(20),
+                Action::Sort(param) => app.set_process_table_sort(param),
+                Action::KillProcess => app.kill_selected_process_from_table(),
+                Action::TogglePin => app.toggle_pin_selected_process(),
+                Action::ToggleProcessTrend => app.toggle_process_trend(),
+                Action::ToggleThreadView => app.toggle_thread_view(),
+                Action::ToggleShowThreads => app.toggle_show_threads(),
+                Action::NextProcessGroupBy => app.next_process_group_by(),
+                Action::ToggleNameGroups => app.toggle_name_groups(),
+                Action::ToggleGroup => app.toggle_selected_group(),
+                Action::ClearUserFilter => app.clear_user_filter(),
+                Action::SortUsers(param) => app.set_user_table_sort(param),
+                Action::ShowUserProcesses => app.show_user_processes(),
+                Action::NextTrendScale => app.next_trend_scale(),
+                Action::ToggleAlertLog => app.toggle_alert_log(),
+                Action::AlertLogNextRow => app.alert_log_next_row(),
+                Action::AlertLogPreviousRow => app.alert_log_previous_row(),
+            }
+        }
+        Event::Mouse(mouse) if mouse.kind == MouseEventKind::Down(MouseButton::Left) && !app.help_visible => {
+            app.click(mouse.column, mouse.row)
+        }
         _ => {}
     }
     Ok(false)
-}
\ No newline at end of file
+}
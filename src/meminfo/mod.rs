@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use sysinfo::System;
+
+// Memory split like free(1), bytes. Buffers, cache, shmem and huge pages come from /proc/meminfo
+// and stay 0 where it's missing, cache is then estimated from available and free memory.
+#[derive(Default)]
+pub struct MemoryInfo {
+    pub total: u64,
+    pub used: u64,              // Not free, buffers or cache
+    pub buffers: u64,
+    pub cache: u64,             // Page cache and reclaimable slab
+    pub free: u64,
+    pub available: u64,         // Free plus what can be reclaimed without swapping
+    pub shmem: u64,             // Shared memory and tmpfs, part of the cache
+    pub huge_pages_total: u64,
+    pub huge_pages_free: u64,
+    pub huge_page_size: u64,
+    pub anon_huge_pages: u64,   // Transparent huge pages
+}
+
+impl MemoryInfo {
+    pub fn read(system: &System) -> Self {
+        let meminfo = read_meminfo();
+        let kb = |key: &str| meminfo.get(key).map(|value| value * 1024);
+
+        let total = system.total_memory();
+        let free = system.free_memory();
+        let available = system.available_memory();
+        let buffers = kb("Buffers").unwrap_or(0);
+        let cache = match kb("Cached") {
+            Some(cached) => cached + kb("SReclaimable").unwrap_or(0),
+            None => available.saturating_sub(free),
+        };
+
+        Self {
+            total,
+            used: total.saturating_sub(free + buffers + cache),
+            buffers,
+            cache,
+            free,
+            available,
+            shmem: kb("Shmem").unwrap_or(0),
+            huge_pages_total: meminfo.get("HugePages_Total").copied().unwrap_or(0),
+            huge_pages_free: meminfo.get("HugePages_Free").copied().unwrap_or(0),
+            huge_page_size: kb("Hugepagesize").unwrap_or(0),
+            anon_huge_pages: kb("AnonHugePages").unwrap_or(0),
+        }
+    }
+}
+
+// Empty outside Linux
+fn read_meminfo() -> HashMap<String, u64> {
+    std::fs::read_to_string("/proc/meminfo")
+        .map(|content| parse_meminfo(&content))
+        .unwrap_or_default()
+}
+
+// "Key:   value kB" lines, values as written (kB or a count)
+fn parse_meminfo(content: &str) -> HashMap<String, u64> {
+    content.lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let value = value.split_whitespace().next()?.parse::<u64>().ok()?;
+            Some((key.to_string(), value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn meminfo_values_are_read_as_written() {
+        let meminfo = parse_meminfo("MemTotal:       16315712 kB\n\
+            Buffers:          123456 kB\n\
+            HugePages_Total:       4\n\
+            Hugepagesize:       2048 kB\n\
+            Broken line\n\
+            Bad:                 n/a\n");
+
+        assert_eq!(meminfo.get("MemTotal"), Some(&16315712));
+        assert_eq!(meminfo.get("Buffers"), Some(&123456));
+        assert_eq!(meminfo.get("HugePages_Total"), Some(&4));
+        assert_eq!(meminfo.get("Hugepagesize"), Some(&2048));
+        assert_eq!(meminfo.len(), 4);
+    }
+}
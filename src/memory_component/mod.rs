@@ -0,0 +1,132 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Cell, Padding, Paragraph, Row, Table, Widget};
+use crate::meminfo::MemoryInfo;
+use crate::units::{Unit, UnitFormat};
+
+const USED_COLOR: Color = Color::Green;
+const BUFFERS_COLOR: Color = Color::Blue;
+const CACHE_COLOR: Color = Color::Yellow;
+const FREE_COLOR: Color = Color::DarkGray;
+const AVAILABLE_COLOR: Color = Color::Cyan;
+
+// Stacked used / buffers / cache / free bar with the available memory under it, and the readout
+pub struct MemoryComponent<'a> {
+    memory: &'a MemoryInfo,
+    unit_format: UnitFormat,
+    focused: bool,
+}
+
+impl<'a> MemoryComponent<'a> {
+    pub fn new(memory: &'a MemoryInfo, unit_format: UnitFormat) -> Self {
+        Self {
+            memory,
+            unit_format,
+            focused: false,
+        }
+    }
+
+    pub fn focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+
+    fn per(&self, value: u64) -> f64 {
+        if self.memory.total == 0 {
+            0.0
+        } else {
+            value as f64 / self.memory.total as f64 * 100.0
+        }
+    }
+
+    // Segment widths of the bar, the rounding goes to the free segment so they fill the width
+    fn stacked_bar(&self, width: u16) -> Line<'static> {
+        let width = width as u64;
+        let cells = |value: u64| (value * width).checked_div(self.memory.total).unwrap_or(0);
+        let used = cells(self.memory.used);
+        let buffers = cells(self.memory.buffers);
+        let cache = cells(self.memory.cache);
+        let free = width.saturating_sub(used + buffers + cache);
+
+        Line::from([(used, USED_COLOR), (buffers, BUFFERS_COLOR), (cache, CACHE_COLOR), (free, FREE_COLOR)]
+            .into_iter()
+            .map(|(cells, color)| Span::styled("█".repeat(cells as usize), Style::new().fg(color)))
+            .collect::<Vec<_>>())
+    }
+
+    // Available memory is the free end of the bar with the reclaimable cache
+    fn available_bar(&self, width: u16) -> Line<'static> {
+        let available = (self.memory.available * width as u64).checked_div(self.memory.total).unwrap_or(0);
+        Line::from(vec![
+            Span::raw(" ".repeat((width as u64).saturating_sub(available) as usize)),
+            Span::styled("▀".repeat(available as usize), Style::new().fg(AVAILABLE_COLOR)),
+        ])
+    }
+}
+
+impl Widget for MemoryComponent<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+
+        let block = Block::bordered().title(" Memory ")
+            .border_style(if self.focused { Style::new().fg(Color::Yellow) } else { Style::new() })
+            .padding(Padding::horizontal(1));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [bar_layout, available_layout, _, readout_layout] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Fill(1),
+        ])
+            .areas(inner);
+
+        Paragraph::new(self.stacked_bar(bar_layout.width)).render(bar_layout, buf);
+        Paragraph::new(self.available_bar(available_layout.width)).render(available_layout, buf);
+
+        let memory = self.memory;
+        let bytes = |value: u64| self.unit_format.format(Unit::Bytes, value as f64);
+        let mut rows = [
+            ("Used", Some(USED_COLOR), memory.used),
+            ("Buffers", Some(BUFFERS_COLOR), memory.buffers),
+            ("Cache", Some(CACHE_COLOR), memory.cache),
+            ("Free", Some(FREE_COLOR), memory.free),
+            ("Available", Some(AVAILABLE_COLOR), memory.available),
+            ("Shmem", None, memory.shmem),
+            ("Total", None, memory.total),
+        ]
+            .into_iter()
+            .map(|(name, color, value)| Row::new([
+                Cell::from(Span::styled(if color.is_some() { "■" } else { " " }, Style::new().fg(color.unwrap_or_default()))),
+                Cell::from(name),
+                Cell::from(bytes(value)),
+                Cell::from(self.unit_format.format(Unit::Percent, self.per(value))),
+            ]))
+            .collect::<Vec<_>>();
+
+        // Reserved huge pages are used memory, transparent ones are part of the process memory
+        let huge_pages_used = memory.huge_pages_total.saturating_sub(memory.huge_pages_free);
+        rows.push(Row::new([
+            Cell::from(" "),
+            Cell::from("Huge pages"),
+            Cell::from(format!("{huge_pages_used} / {} x {}", memory.huge_pages_total, bytes(memory.huge_page_size))),
+            Cell::from(self.unit_format.format(Unit::Percent, self.per(memory.huge_pages_total * memory.huge_page_size))),
+        ]));
+        rows.push(Row::new([
+            Cell::from(" "),
+            Cell::from("THP"),
+            Cell::from(bytes(memory.anon_huge_pages)),
+            Cell::from(self.unit_format.format(Unit::Percent, self.per(memory.anon_huge_pages))),
+        ]));
+
+        Table::new(rows, [
+            Constraint::Length(1),
+            Constraint::Length(11),
+            Constraint::Length(22),
+            Constraint::Length(7),
+        ])
+            .render(readout_layout, buf);
+    }
+}
@@ -0,0 +1,146 @@
+use std::time::Instant;
+
+use crate::trend_component::aggregate;
+
+// Samples kept per metric
+pub const METRIC_HISTORY_CAPACITY: usize = 500;
+// Samples kept per metric when a history file is loaded, a day of one-second samples.
+// Longer files are downsampled to fit, trends aggregate all samples on every frame,
+// so this bounds the drawing work too.
+pub const LOADED_HISTORY_CAPACITY: usize = 86_400;
+
+// Metrics sampled on every tick
+#[derive(Clone, Copy, PartialEq)]
+pub enum Metric {
+    Cpu,            // %
+    Mem,            // %
+    Swap,           // %
+    Load1,
+    Load5,
+    Load15,
+    NetRx,          // bytes/s
+    NetTx,          // bytes/s
+    Processes,      // Process count
+    Running,        // Running process count
+}
+
+impl Metric {
+    pub const ALL: [Metric; 10] = [
+        Metric::Cpu, Metric::Mem, Metric::Swap,
+        Metric::Load1, Metric::Load5, Metric::Load15,
+        Metric::NetRx, Metric::NetTx,
+        Metric::Processes, Metric::Running,
+    ];
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+// The newest capacity (time, value) samples. Older samples are dropped in batches of capacity,
+// so pushing stays cheap and trends can borrow the samples as one slice.
+pub struct MetricBuffer {
+    capacity: usize,
+    samples: Vec<(f64, f64)>,
+}
+
+impl MetricBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            samples: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, time: f64, value: f64) {
+        if self.samples.len() >= 2 * self.capacity {
+            self.samples.drain(..self.capacity);
+        }
+        self.samples.push((time, value));
+    }
+
+    pub fn points(&self) -> &[(f64, f64)] {
+        &self.samples[self.samples.len().saturating_sub(self.capacity)..]
+    }
+}
+
+// Sampled metrics owned by App. Filled only on sampling ticks, widgets just read it.
+// Time is in seconds since the start, or continues from the end of a loaded history.
+pub struct MetricHistory {
+    start: Instant,
+    time_offset: f64,
+    buffers: Vec<MetricBuffer>,
+}
+
+impl MetricHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            start: Instant::now(),
+            time_offset: 0.0,
+            buffers: Metric::ALL.iter().map(|_| MetricBuffer::new(capacity)).collect(),
+        }
+    }
+
+    // Put recorded points before the live samples. Points that don't fit are reduced to
+    // the min and max of equal time buckets, so the whole range and its spikes stay visible.
+    pub fn load(&mut self, metric: Metric, points: &[(f64, f64)]) {
+        let buffer = &mut self.buffers[metric.index()];
+        if points.len() > buffer.capacity {
+            for bucket in aggregate(points, buffer.capacity / 2) {
+                buffer.push(bucket.time, bucket.min);
+                buffer.push(bucket.time, bucket.max);
+            }
+        } else {
+            for &(time, value) in points {
+                buffer.push(time, value);
+            }
+        }
+        if let Some(&(time, _)) = points.last() {
+            self.time_offset = self.time_offset.max(time);
+        }
+    }
+
+    // Record one sample of every metric with the same timestamp
+    pub fn sample(&mut self, mut value: impl FnMut(Metric) -> f64) {
+        let time = self.time_offset + self.start.elapsed().as_secs_f64();
+        for metric in Metric::ALL {
+            self.buffers[metric.index()].push(time, value(metric));
+        }
+    }
+
+    pub fn get(&self, metric: Metric) -> &MetricBuffer {
+        &self.buffers[metric.index()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffer_keeps_the_newest_samples() {
+        let mut buffer = MetricBuffer::new(3);
+        for i in 0..10 {
+            buffer.push(i as f64, i as f64 * 10.0);
+        }
+        assert_eq!(buffer.points(), &[(7.0, 70.0), (8.0, 80.0), (9.0, 90.0)]);
+    }
+
+    #[test]
+    fn load_keeps_points_that_fit() {
+        let mut history = MetricHistory::new(3);
+        history.load(Metric::Cpu, &[(1.0, 10.0), (2.0, 20.0), (3.0, 30.0)]);
+        assert_eq!(history.get(Metric::Cpu).points(), &[(1.0, 10.0), (2.0, 20.0), (3.0, 30.0)]);
+        assert!(history.get(Metric::Mem).points().is_empty());
+    }
+
+    #[test]
+    fn load_downsamples_the_whole_range() {
+        let mut history = MetricHistory::new(4);
+        let points: Vec<(f64, f64)> = (0..100).map(|i| (i as f64, if i == 10 { 99.0 } else { 1.0 })).collect();
+        history.load(Metric::Cpu, &points);
+        // Two buckets of 50 points, the first one keeps the spike
+        assert_eq!(history.get(Metric::Cpu).points(), &[(24.75, 1.0), (24.75, 99.0), (74.25, 1.0), (74.25, 1.0)]);
+        assert_eq!(history.time_offset, 99.0);
+    }
+}
@@ -0,0 +1,71 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::style::Color::{Black, Cyan};
+use ratatui::widgets::{Block, Cell, HighlightSpacing, Row, StatefulWidget, Table, TableState};
+use crate::app::NetworkInterface;
+use crate::units::{Unit, UnitFormat};
+
+pub struct NetworkTableComponent<'a> {
+    network_interfaces: &'a [NetworkInterface],
+    unit_format: UnitFormat,
+    focused: bool,
+}
+
+impl<'a> NetworkTableComponent<'a> {
+    pub fn new(network_interfaces: &'a [NetworkInterface], unit_format: UnitFormat) -> Self {
+        Self {
+            network_interfaces,
+            unit_format,
+            focused: false,
+        }
+    }
+
+    pub fn focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+}
+
+impl StatefulWidget for NetworkTableComponent<'_> {
+    type State = TableState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+
+        let header = ["INTERFACE", "RX", "TX", "TOTAL RX", "TOTAL TX"]
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .style(Style::new().fg(Color::Rgb(0, 0, 0)).bg(Color::Green))
+            .height(1);
+
+        let rows = self.network_interfaces.iter().map(|interface| {
+            Row::new([
+                Cell::from(interface.name.as_str()),
+                Cell::from(self.unit_format.format(Unit::BytesPerSec, interface.rx_rate)),
+                Cell::from(self.unit_format.format(Unit::BytesPerSec, interface.tx_rate)),
+                Cell::from(self.unit_format.format(Unit::Bytes, interface.total_rx as f64)),
+                Cell::from(self.unit_format.format(Unit::Bytes, interface.total_tx as f64)),
+            ])
+                .height(1)
+        });
+
+        let t = Table::new(
+            rows,
+            [
+                Constraint::Fill(1),
+                Constraint::Length(14),
+                Constraint::Length(14),
+                Constraint::Length(14),
+                Constraint::Length(14),
+            ],
+        )
+            .header(header)
+            .block(Block::bordered().title(" Interfaces ")
+                .border_style(if self.focused { Style::new().fg(Color::Yellow) } else { Style::new() }))
+            .row_highlight_style(Style::new().fg(Black).bg(Cyan))
+            .highlight_spacing(HighlightSpacing::Always);
+
+        t.render(area, buf, state);
+    }
+}
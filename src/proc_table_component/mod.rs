@@ -5,7 +5,10 @@ use ratatui::style::Color::{Black, Cyan};
 use ratatui::text::{Line, Text};
 use ratatui::widgets::{Cell, HighlightSpacing, Row, StatefulWidget, Table, TableState};
 use crate::process_object::ProcessObject;
+use crate::process_object::group::ProcessGroupBy;
+use crate::units::{Unit, UnitFormat};
 
+#[derive(Clone, Copy, PartialEq, clap::ValueEnum)]
 pub enum SortTableParam {
     Pid,
     User,
@@ -19,15 +22,42 @@ pub enum SortTableParam {
 pub struct ProcTableComponent<'a> {
     process_table_items_vec: &'a Vec<ProcessObject>,
         process_table_sort_param: &'a SortTableParam,
+    unit_format: UnitFormat,
+    group_by: ProcessGroupBy,
+    thread_view: bool,
+    focused: bool,
 }
 
 impl<'a> ProcTableComponent<'a> {
-    pub fn new(process_table_items_vec: &'a Vec<ProcessObject>, process_table_sort_param: &'a SortTableParam) -> Self {
+    pub fn new(process_table_items_vec: &'a Vec<ProcessObject>, process_table_sort_param: &'a SortTableParam,
+               unit_format: UnitFormat) -> Self {
         Self {
             process_table_items_vec,
             process_table_sort_param,
+            unit_format,
+            group_by: ProcessGroupBy::None,
+            thread_view: false,
+            focused: false,
         }
     }
+
+    // The Name column shows the group keys while grouped
+    pub fn group_by(mut self, group_by: ProcessGroupBy) -> Self {
+        self.group_by = group_by;
+        self
+    }
+
+    // Threads of one process, TID and State instead of PID and Command
+    pub fn thread_view(mut self, thread_view: bool) -> Self {
+        self.thread_view = thread_view;
+        self
+    }
+
+    // The table has no border, the focus is shown on the header
+    pub fn focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
 }
 
 impl StatefulWidget for ProcTableComponent<'_> {
@@ -35,17 +65,19 @@ impl StatefulWidget for ProcTableComponent<'_> {
 
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
 
+        let (id_title, command_title) = if self.thread_view { ("TID", "State") } else { ("PID", "Command") };
+
         let sort_param = match self.process_table_sort_param {
-            SortTableParam::Pid => "PID",
+            SortTableParam::Pid => id_title,
             SortTableParam::User => "USER",
             SortTableParam::Cpu => "CPU%",
             SortTableParam::Mem => "MEM%",
             SortTableParam::Time => "TIME",
-            SortTableParam::Name => "Name",
-            SortTableParam::Command => "Command",
+            SortTableParam::Name => self.group_by.title(),
+            SortTableParam::Command => command_title,
         };
 
-        let header = ["PID", "USER", "CPU%", "MEM%", "TIME", "Name", "Command"]
+        let header = [id_title, "USER", "CPU%", "MEM%", "RES", "TIME", self.group_by.title(), command_title]
             .into_iter()
             .map(|i| {
                 if sort_param == i {
@@ -55,27 +87,53 @@ impl StatefulWidget for ProcTableComponent<'_> {
                 }
             })
             .collect::<Row>()
-            .style(Style::new().fg(Color::Rgb(0, 0, 0)).bg(Color::Green))
+            .style(Style::new().fg(Color::Rgb(0, 0, 0)).bg(if self.focused { Color::Yellow } else { Color::Green }))
             .height(1);
 
         let rows = self.process_table_items_vec.iter().map(|data| {
-            let item = data.ref_array();
+            let mut item = data.ref_array().map(|content| content.to_string());
+            // CPU% and MEM% are kept unrounded for sorting
+            for per in &mut item[2..4] {
+                *per = self.unit_format.number(per.parse::<f64>().unwrap_or(0.0));
+            }
+            // Group rows get an expand marker, their members are indented
+            if let Some(group) = &data.group {
+                item[5] = format!("{} {}", if group.expanded { "▾" } else { "▸" }, item[5]);
+            } else if data.group_member {
+                item[5] = format!("  {}", item[5]);
+            }
+            // Resident memory goes after MEM%, watched processes that are not running have none
+            let mut item = item.to_vec();
+            item.insert(4, if data.is_running() { self.unit_format.format(Unit::Bytes, data.rss as f64) } else { String::new() });
+            // Pinned processes are marked, watched processes that are not running are dimmed, threads are dim
+            let style = match (data.pinned, data.is_running(), data.is_group()) {
+                (_, _, true) => Style::new().fg(Color::Cyan).bold(),
+                (true, true, _) => Style::new().fg(Color::Yellow).bold(),
+                (true, false, _) => Style::new().fg(Color::DarkGray).italic(),
+                _ if data.thread && !self.thread_view => Style::new().dim(),
+                _ => Style::new(),
+            };
             item.into_iter()
-                .map(|content| Cell::from(Text::from(format!("{}", content))))
+                .map(|content| Cell::from(Text::from(content)))
                 .collect::<Row>()
-                .style(Style::new())
+                .style(style)
                 .height(1)
         });
 
         let bar = " ► ";
 
+        // CPU% of many cores takes 4 integer digits too
+        let percent_width = self.unit_format.width(Unit::Plain) as u16;
+        let bytes_width = self.unit_format.width(Unit::Bytes) as u16;
+
         let t = Table::new(
             rows,
             [
                 Constraint::Length(5),
                 Constraint::Length(10),
-                Constraint::Length(4),
-                Constraint::Length(4),
+                Constraint::Length(percent_width),
+                Constraint::Length(percent_width),
+                Constraint::Length(bytes_width),
                 Constraint::Length(12),
                 Constraint::Length(25),
                 Constraint::Fill(1),
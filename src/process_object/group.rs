@@ -0,0 +1,269 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use crate::process_object::ProcessObject;
+
+// Group key of processes without a unit or container
+const NO_GROUP: &str = "(none)";
+
+// Systemd unit types a process can belong to
+const UNIT_SUFFIXES: [&str; 3] = [".service", ".scope", ".slice"];
+
+// Container IDs are 64 hex digits, shown shortened like docker ps
+const CONTAINER_ID_LEN: usize = 64;
+const CONTAINER_ID_SHORT_LEN: usize = 12;
+
+// Process table grouping, "g" cycles the cgroup modes, "c" toggles the name one
+#[derive(Clone, Copy, PartialEq, Default)]
+pub enum ProcessGroupBy {
+    #[default]
+    None,
+    Name,           // Same process name, worker pools and browsers
+    Cgroup,         // Full cgroup path
+    Unit,           // Systemd unit, the innermost one in the cgroup path
+    Container,      // Container ID found in the cgroup path
+}
+
+impl ProcessGroupBy {
+    pub fn next(&self) -> Self {
+        match self {
+            ProcessGroupBy::None | ProcessGroupBy::Name => ProcessGroupBy::Cgroup,
+            ProcessGroupBy::Cgroup => ProcessGroupBy::Unit,
+            ProcessGroupBy::Unit => ProcessGroupBy::Container,
+            ProcessGroupBy::Container => ProcessGroupBy::None,
+        }
+    }
+
+    // Header of the Name column
+    pub fn title(&self) -> &'static str {
+        match self {
+            ProcessGroupBy::None | ProcessGroupBy::Name => "Name",
+            ProcessGroupBy::Cgroup => "CGROUP",
+            ProcessGroupBy::Unit => "UNIT",
+            ProcessGroupBy::Container => "CONTAINER",
+        }
+    }
+
+    // The cgroup is read only for the cgroup modes
+    pub fn needs_cgroup(&self) -> bool {
+        matches!(self, ProcessGroupBy::Cgroup | ProcessGroupBy::Unit | ProcessGroupBy::Container)
+    }
+
+    fn key(&self, process: &ProcessObject) -> String {
+        let cgroup = process.cgroup.as_str();
+        let key = match self {
+            ProcessGroupBy::None => None,
+            ProcessGroupBy::Name => Some(process.name.clone()),
+            ProcessGroupBy::Cgroup => Some(cgroup.to_string()).filter(|cgroup| !cgroup.is_empty()),
+            ProcessGroupBy::Unit => cgroup.rsplit('/')
+                .find(|part| UNIT_SUFFIXES.iter().any(|suffix| part.ends_with(suffix)))
+                .map(|unit| unit.to_string()),
+            ProcessGroupBy::Container => container_id(cgroup),
+        };
+        key.unwrap_or_else(|| NO_GROUP.to_string())
+    }
+}
+
+// Cgroup path of a process: the v2 unified one, the v1 systemd one, then any v1 controller path.
+// The root path "/" only when all are at the root, empty when unknown.
+pub fn read_cgroup(pid: &str) -> String {
+    std::fs::read_to_string(format!("/proc/{pid}/cgroup"))
+        .map(|content| parse_cgroup(&content))
+        .unwrap_or_default()
+}
+
+// "hierarchy-ID:controllers:path" lines
+fn parse_cgroup(content: &str) -> String {
+    let mut paths = content.lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, ':').skip(1);
+            fields.next().zip(fields.next())
+        })
+        .collect::<Vec<_>>();
+    paths.sort_by_key(|(controllers, _)| match *controllers {
+        "" => 0,
+        "name=systemd" => 1,
+        _ => 2,
+    });
+    paths.iter()
+        .map(|(_, path)| *path)
+        .find(|path| *path != "/")
+        .or(paths.first().map(|(_, path)| *path))
+        .unwrap_or_default()
+        .to_string()
+}
+
+// docker-<id>.scope, /docker/<id>, cri-containerd-<id>.scope, libpod-<id>.scope ...
+fn container_id(cgroup: &str) -> Option<String> {
+    cgroup.split(|c: char| !c.is_ascii_hexdigit())
+        .find(|part| part.len() == CONTAINER_ID_LEN)
+        .map(|id| id[..CONTAINER_ID_SHORT_LEN].to_string())
+}
+
+// Summary row fields of a group
+#[derive(Clone)]
+pub struct ProcessGroupRow {
+    pub expanded: bool,
+}
+
+// Replace the sorted processes with one summary row per group, sorted the same way.
+// Members of expanded groups follow their summary row in the original order.
+// Names of a single process stay plain rows, only the repeated ones are collapsed.
+pub fn group(processes: Vec<ProcessObject>, group_by: ProcessGroupBy, expanded: &HashSet<String>,
+             sort_fn: Option<fn(&ProcessObject, &ProcessObject) -> Ordering>) -> Vec<ProcessObject> {
+
+    let mut groups = HashMap::<String, Vec<ProcessObject>>::new();
+    for process in processes {
+        groups.entry(group_by.key(&process)).or_default().push(process);
+    }
+
+    // (row, members)
+    let mut rows = groups.into_iter()
+        .map(|(key, mut members)| match group_by {
+            ProcessGroupBy::Name if members.len() == 1 => (members.remove(0), Vec::new()),
+            _ => (summary(&key, &members, expanded.contains(&key)), members),
+        })
+        .collect::<Vec<_>>();
+    if let Some(sort_fn) = sort_fn {
+        rows.sort_by(|a, b| sort_fn(&a.0, &b.0));
+    }
+
+    let mut table_rows = Vec::new();
+    for (row, members) in rows {
+        let is_expanded = row.group.as_ref().is_some_and(|group| group.expanded);
+        table_rows.push(row);
+        if is_expanded {
+            table_rows.extend(members.into_iter().map(|member| ProcessObject { group_member: true, ..member }));
+        }
+    }
+    table_rows
+}
+
+// The lowest PID keeps PID sorting working, CPU, MEM and RES are summed, TIME is the oldest member's
+fn summary(key: &str, members: &[ProcessObject], expanded: bool) -> ProcessObject {
+    let sum = |value: fn(&ProcessObject) -> &str| members.iter()
+        .map(|member| value(member).parse::<f64>().unwrap_or(0.0))
+        .sum::<f64>();
+    let oldest = members.iter().max_by_key(|member| member.time_sec);
+    let first_user = members.first().map(|member| member.user.clone()).unwrap_or_default();
+
+    ProcessObject {
+        pid: members.iter().filter_map(|member| member.pid.parse::<u32>().ok()).min().unwrap_or(0).to_string(),
+        user: if members.iter().all(|member| member.user == first_user) { first_user } else { "*".to_string() },
+        cpu: sum(ProcessObject::cpu).to_string(),
+        mem: sum(ProcessObject::mem).to_string(),
+        rss: members.iter().map(|member| member.rss).sum(),
+        time_sec: oldest.map(|member| member.time_sec).unwrap_or(0),
+        time: oldest.map(|member| member.time.clone()).unwrap_or_default(),
+        name: key.to_string(),
+        command: match members.len() {
+            1 => "1 process".to_string(),
+            count => format!("{count} processes"),
+        },
+        group: Some(ProcessGroupRow {
+            expanded,
+        }),
+        ..ProcessObject::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCKER_ID: &str = "4f1b6b0c9d8e7a6f5e4d3c2b1a0f9e8d7c6b5a4f3e2d1c0b9a8f7e6d5c4b3a2f";
+
+    fn process(pid: &str, name: &str, cpu: &str, time_sec: u64) -> ProcessObject {
+        ProcessObject {
+            pid: pid.to_string(),
+            user: "root".to_string(),
+            cpu: cpu.to_string(),
+            mem: "1".to_string(),
+            rss: 100,
+            time_sec,
+            name: name.to_string(),
+            ..ProcessObject::new()
+        }
+    }
+
+    fn key(group_by: ProcessGroupBy, cgroup: &str) -> String {
+        group_by.key(&ProcessObject { cgroup: cgroup.to_string(), ..ProcessObject::new() })
+    }
+
+    #[test]
+    fn cgroup_files_prefer_the_unified_then_the_systemd_hierarchy() {
+        let cases = [
+            ("0::/system.slice/nginx.service\n", "/system.slice/nginx.service"),
+            ("12:cpu,cpuacct:/docker/abc\n1:name=systemd:/system.slice/docker.service\n0::/\n",
+             "/system.slice/docker.service"),
+            ("12:memory:/user.slice\n11:cpu:/user.slice\n", "/user.slice"),
+            ("1:name=systemd:/\n0::/\n", "/"),
+            ("", ""),
+            ("malformed\nno colons here\n", ""),
+        ];
+        for (content, cgroup) in cases {
+            assert_eq!(parse_cgroup(content), cgroup, "{content:?}");
+        }
+    }
+
+    #[test]
+    fn container_ids_are_found_in_runtime_paths() {
+        let short = &DOCKER_ID[..CONTAINER_ID_SHORT_LEN];
+        let cases = [
+            (format!("/system.slice/docker-{DOCKER_ID}.scope"), Some(short)),
+            (format!("/docker/{DOCKER_ID}"), Some(short)),
+            (format!("/machine.slice/libpod-{DOCKER_ID}.scope/container"), Some(short)),
+            (format!("/kubepods.slice/kubepods-besteffort.slice/cri-containerd-{DOCKER_ID}.scope"), Some(short)),
+            (format!("/docker/{}", &DOCKER_ID[1..]), None),
+            ("/system.slice/nginx.service".to_string(), None),
+            (String::new(), None),
+        ];
+        for (cgroup, id) in cases {
+            assert_eq!(container_id(&cgroup).as_deref(), id, "{cgroup}");
+        }
+    }
+
+    #[test]
+    fn group_keys_fall_back_to_none() {
+        let cases = [
+            (ProcessGroupBy::Cgroup, "/system.slice/nginx.service", "/system.slice/nginx.service"),
+            (ProcessGroupBy::Cgroup, "", NO_GROUP),
+            (ProcessGroupBy::Unit, "/system.slice/nginx.service", "nginx.service"),
+            (ProcessGroupBy::Unit, "/user.slice/user-1000.slice/session-2.scope", "session-2.scope"),
+            (ProcessGroupBy::Unit, "/user.slice/user-1000.slice/user@1000.service/app.slice/foot.scope", "foot.scope"),
+            (ProcessGroupBy::Unit, "/docker/abc", NO_GROUP),
+            (ProcessGroupBy::Container, "/system.slice/nginx.service", NO_GROUP),
+            (ProcessGroupBy::None, "/system.slice/nginx.service", NO_GROUP),
+        ];
+        for (group_by, cgroup, expected) in cases {
+            assert_eq!(key(group_by, cgroup), expected, "{cgroup}");
+        }
+        assert_eq!(key(ProcessGroupBy::Container, &format!("/docker/{DOCKER_ID}")), DOCKER_ID[..CONTAINER_ID_SHORT_LEN]);
+    }
+
+    #[test]
+    fn names_of_several_processes_are_collapsed() {
+        let processes = vec![
+            process("30", "nginx", "1.5", 10),
+            process("12", "sshd", "0.5", 50),
+            process("20", "nginx", "2", 40),
+        ];
+        let by_cpu: fn(&ProcessObject, &ProcessObject) -> Ordering =
+            |a, b| b.cpu().parse::<f64>().unwrap().total_cmp(&a.cpu().parse::<f64>().unwrap());
+
+        let rows = group(processes.clone(), ProcessGroupBy::Name, &HashSet::new(), Some(by_cpu));
+        assert_eq!(rows.iter().map(|row| (row.pid.as_str(), row.name.as_str())).collect::<Vec<_>>(),
+                   [("20", "nginx"), ("12", "sshd")]);
+        let nginx = &rows[0];
+        assert!(nginx.group.as_ref().is_some_and(|group| !group.expanded));
+        assert_eq!((nginx.cpu.as_str(), nginx.mem.as_str(), nginx.rss, nginx.time_sec), ("3.5", "2", 200, 40));
+        assert_eq!(nginx.command, "2 processes");
+        // A single process stays a plain row
+        assert!(rows[1].group.is_none());
+
+        let expanded = HashSet::from(["nginx".to_string()]);
+        let rows = group(processes, ProcessGroupBy::Name, &expanded, Some(by_cpu));
+        assert_eq!(rows.iter().map(|row| (row.pid.as_str(), row.group_member)).collect::<Vec<_>>(),
+                   [("20", false), ("30", true), ("20", true), ("12", false)]);
+    }
+}
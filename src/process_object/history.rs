@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+
+use sysinfo::{Pid, Process};
+
+use crate::trend_component::TrendPoints;
+
+// Samples kept per process
+const PROCESS_HISTORY_CAPACITY: usize = 300;
+
+// CPU% and RSS of one process over the sampling ticks
+#[derive(Default)]
+pub struct ProcessHistory {
+    pub cpu: TrendPoints,
+    pub rss: TrendPoints,       // Bytes
+}
+
+impl ProcessHistory {
+    fn push(&mut self, tick: f64, cpu: f64, rss: f64) {
+        if self.cpu.len() >= PROCESS_HISTORY_CAPACITY {
+            self.cpu.remove(0);
+            self.rss.remove(0);
+        }
+        self.cpu.push((tick, cpu));
+        self.rss.push((tick, rss));
+    }
+}
+
+// Bounded per-PID history, processes that exit are dropped
+#[derive(Default)]
+pub struct ProcessHistoryStore {
+    tick: f64,
+    histories: HashMap<Pid, ProcessHistory>,
+}
+
+impl ProcessHistoryStore {
+    pub fn update(&mut self, processes: &HashMap<Pid, Process>) {
+        self.tick += 1.0;
+        self.histories.retain(|pid, _| processes.contains_key(pid));
+        for (pid, process) in processes {
+            self.histories.entry(*pid).or_default()
+                .push(self.tick, process.cpu_usage() as f64, process.memory() as f64);
+        }
+    }
+
+    pub fn get(&self, pid: &str) -> Option<&ProcessHistory> {
+        pid.parse::<u32>().ok().and_then(|pid| self.histories.get(&Pid::from_u32(pid)))
+    }
+}
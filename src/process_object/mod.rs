@@ -1,15 +1,32 @@
+pub mod group;
+pub mod history;
+
 use std::cmp::Ordering;
 
+use group::ProcessGroupRow;
+
 #[derive(Clone)]
 pub struct ProcessObject {
     pub pid: String,
     pub user: String,
     pub cpu: String,
     pub mem: String,
+    pub rss: u64,               // Resident memory, bytes
     pub time_sec: u64,
     pub time: String,
     pub name: String,
     pub command: String,
+    pub pinned: bool,           // Matched by the watch list
+    pub cgroup: String,         // Read only while the table is grouped
+    pub group: Option<ProcessGroupRow>,     // Summary row of a group
+    pub group_member: bool,     // Row of an expanded group
+    pub thread: bool,           // Userland thread, the PID is the TID
+}
+
+impl Default for ProcessObject {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ProcessObject {
@@ -19,13 +36,37 @@ impl ProcessObject {
             user: "".to_string(),
             cpu: "".to_string(),
             mem: "".to_string(),
+            rss: 0,
             time_sec: 0,
             time: "".to_string(),
             name: "".to_string(),
             command: "".to_string(),
+            pinned: false,
+            cgroup: "".to_string(),
+            group: None,
+            group_member: false,
+            thread: false,
         }
     }
 
+    // Placeholder row for a watched process that is not running
+    pub fn not_running(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            command: "not running".to_string(),
+            pinned: true,
+            ..Self::new()
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.pid.is_empty()
+    }
+
+    pub fn is_group(&self) -> bool {
+        self.group.is_some()
+    }
+
     pub const fn ref_array(&self) -> [&String; 7] {
         [&self.pid, &self.user, &self.cpu, &self.mem, &self.time, &self.name, &self.command]
     }
@@ -57,15 +98,11 @@ impl PartialEq for ProcessObject {
     fn eq(&self, other: &Self) -> bool {
         self.pid.parse::<u32>() == other.pid.parse::<u32>()
     }
-
-    fn ne(&self, other: &Self) -> bool {
-        !self.eq(other)
-    }
 }
 
 impl PartialOrd for ProcessObject {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.pid.partial_cmp(&other.pid)
+        Some(self.cmp(other))
     }
 
     fn lt(&self, other: &Self) -> bool {
@@ -90,7 +127,8 @@ impl Eq for ProcessObject{
 
 impl Ord for ProcessObject {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.pid.cmp(&other.pid)
+        // Placeholder rows without a PID come first
+        self.pid.parse::<u32>().ok().cmp(&other.pid.parse::<u32>().ok())
     }
 
     fn max(self, other: Self) -> Self
@@ -0,0 +1,277 @@
+use std::io::Write;
+use std::thread;
+use std::time::Duration;
+
+use serde::Serialize;
+use sysinfo::System;
+
+use crate::app::App;
+use crate::config::Config;
+use crate::cli_parser::SnapshotFormat;
+use crate::proc_table_component::SortTableParam;
+use crate::process_object::ProcessObject;
+use crate::units::{run_time, Unit, UnitFormat};
+use crate::AppResult;
+
+// Snapshot JSON schema, bump SNAPSHOT_VERSION on any incompatible change.
+//
+// {
+//   "version": 1,
+//   "timestamp": "2024-12-01T10:00:00+03:00",   RFC 3339 local time
+//   "host_name": "server01",
+//   "uptime": 12345,                             seconds
+//   "cpu": { "usage": 12.5, "cores": [10.0, 15.0] },   percent
+//   "memory": { "total": 16777216, "used": 8388608, "usage": 50.0 },   bytes, percent
+//   "swap": { "total": 0, "used": 0, "usage": 0.0 },
+//   "load_average": { "one": 0.5, "five": 0.4, "fifteen": 0.3 },
+//   "processes": [
+//     { "pid": 1, "user": "root", "cpu": 0.0, "mem": 0.1, "rss": 12582912, "run_time": 12345,
+//       "name": "systemd", "command": "/usr/lib/systemd/systemd" }
+//   ]
+// }
+//
+// Processes are sorted by CPU usage, highest first.
+
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+pub struct Snapshot {
+    pub version: u32,
+    pub timestamp: String,
+    pub host_name: String,
+    pub uptime: u64,
+    pub cpu: CpuSnapshot,
+    pub memory: MemorySnapshot,
+    pub swap: MemorySnapshot,
+    pub load_average: LoadAverageSnapshot,
+    pub processes: Vec<ProcessSnapshot>,
+}
+
+#[derive(Serialize)]
+pub struct CpuSnapshot {
+    pub usage: f32,
+    pub cores: Vec<f32>,
+}
+
+#[derive(Serialize)]
+pub struct MemorySnapshot {
+    pub total: u64,
+    pub used: u64,
+    pub usage: f64,
+}
+
+#[derive(Serialize)]
+pub struct LoadAverageSnapshot {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+}
+
+#[derive(Serialize)]
+pub struct ProcessSnapshot {
+    pub pid: u32,
+    pub user: String,
+    pub cpu: f32,
+    pub mem: f64,
+    pub rss: u64,
+    pub run_time: u64,
+    pub name: String,
+    pub command: String,
+}
+
+impl MemorySnapshot {
+    fn new(total: u64, used: u64) -> Self {
+        Self {
+            total,
+            used,
+            usage: if total == 0 { 0.0 } else { (used as f64 / total as f64) * 100.0 },
+        }
+    }
+}
+
+// Running processes by CPU usage, highest first. Watched processes are not pinned and
+// the "not running" rows of the watch list are left out.
+pub fn top_processes(app: &App) -> Vec<&ProcessObject> {
+    let cpu = |process: &ProcessObject| process.cpu().parse::<f32>().unwrap_or(0.0);
+    let mut processes = app.process_table_items_vec.iter()
+        .filter(|process| process.is_running())
+        .collect::<Vec<_>>();
+    processes.sort_by(|a, b| cpu(b).total_cmp(&cpu(a)));
+    processes
+}
+
+impl Snapshot {
+    // Sample the system twice with the given interval, CPU usage is computed between samples
+    // Alert rules are left out, a snapshot must not run their commands
+    pub fn sample(duration: Duration, config: Config) -> AppResult<Self> {
+        let config = Config { alert_rules: Vec::new(), ..config };
+        let mut app = App::new(false, String::new(), duration.as_millis() as u64, config)?;
+
+        app.update_state();
+        thread::sleep(duration.max(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL));
+        app.set_process_table_sort(SortTableParam::Cpu);
+        app.update_state();
+
+        Ok(Self::from_app(&app))
+    }
+
+    fn from_app(app: &App) -> Self {
+        let system = &app.system_state;
+        let load_average = System::load_average();
+
+        Self {
+            version: SNAPSHOT_VERSION,
+            timestamp: app.clock.to_rfc3339(),
+            host_name: System::host_name().unwrap_or_default(),
+            uptime: System::uptime(),
+            cpu: CpuSnapshot {
+                usage: system.global_cpu_usage(),
+                cores: system.cpus().iter().map(|cpu| cpu.cpu_usage()).collect(),
+            },
+            memory: MemorySnapshot::new(system.total_memory(), system.used_memory()),
+            swap: MemorySnapshot::new(system.total_swap(), system.used_swap()),
+            load_average: LoadAverageSnapshot {
+                one: load_average.one,
+                five: load_average.five,
+                fifteen: load_average.fifteen,
+            },
+            processes: top_processes(app).into_iter()
+                .map(|process| ProcessSnapshot {
+                    pid: process.pid().parse::<u32>().unwrap_or(0),
+                    user: process.user().to_string(),
+                    cpu: process.cpu().parse::<f32>().unwrap_or(0.0),
+                    mem: process.mem().parse::<f64>().unwrap_or(0.0),
+                    rss: process.rss,
+                    run_time: process.time_sec,
+                    name: process.name().to_string(),
+                    command: process.command().to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    fn write_json(&self, out: &mut impl Write) -> AppResult<()> {
+        serde_json::to_writer_pretty(&mut *out, self)?;
+        writeln!(out)?;
+        Ok(())
+    }
+
+    // Two CSV tables separated by an empty line: system totals, then the process list
+    fn write_csv(&self, out: &mut impl Write) -> AppResult<()> {
+        let mut writer = csv::Writer::from_writer(&mut *out);
+        writer.write_record(["timestamp", "host_name", "uptime", "cpu", "mem_total", "mem_used",
+            "swap_total", "swap_used", "load_1", "load_5", "load_15"])?;
+        writer.write_record([
+            self.timestamp.clone(), self.host_name.clone(), self.uptime.to_string(),
+            self.cpu.usage.to_string(),
+            self.memory.total.to_string(), self.memory.used.to_string(),
+            self.swap.total.to_string(), self.swap.used.to_string(),
+            self.load_average.one.to_string(), self.load_average.five.to_string(),
+            self.load_average.fifteen.to_string(),
+        ])?;
+        writer.flush()?;
+        drop(writer);
+
+        writeln!(out)?;
+
+        let mut writer = csv::Writer::from_writer(&mut *out);
+        writer.write_record(["pid", "user", "cpu", "mem", "rss", "run_time", "name", "command"])?;
+        for process in &self.processes {
+            writer.write_record([
+                process.pid.to_string(), process.user.clone(), process.cpu.to_string(),
+                process.mem.to_string(), process.rss.to_string(), process.run_time.to_string(),
+                process.name.clone(), process.command.clone(),
+            ])?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    // Human-readable values, JSON and CSV keep bytes and unrounded percents
+    fn write_text(&self, units: UnitFormat, out: &mut impl Write) -> AppResult<()> {
+        let bytes = |value: u64| units.format(Unit::Bytes, value as f64);
+        let per = |value: f64| units.format(Unit::Percent, value);
+
+        writeln!(out, "Host name: {}    Time: {}    Uptime: {}",
+                 self.host_name, self.timestamp, run_time(self.uptime))?;
+        writeln!(out, "CPU: {}    Load average: {} {} {}",
+                 per(self.cpu.usage as f64), units.number(self.load_average.one),
+                 units.number(self.load_average.five), units.number(self.load_average.fifteen))?;
+        writeln!(out, "Memory: {} / {} ({})    Swap: {} / {} ({})",
+                 bytes(self.memory.used), bytes(self.memory.total), per(self.memory.usage),
+                 bytes(self.swap.used), bytes(self.swap.total), per(self.swap.usage))?;
+        writeln!(out)?;
+        let (per_width, bytes_width) = (units.width(Unit::Plain), units.width(Unit::Bytes));
+        writeln!(out, "{:>7} {:<12} {:>per_width$} {:>per_width$} {:>bytes_width$} {:>10} {:<25} COMMAND",
+                 "PID", "USER", "CPU%", "MEM%", "RES", "TIME", "NAME")?;
+        for process in &self.processes {
+            writeln!(out, "{:>7} {:<12} {:>per_width$} {:>per_width$} {:>bytes_width$} {:>10} {:<25} {}",
+                     process.pid, process.user, units.number(process.cpu as f64), units.number(process.mem),
+                     bytes(process.rss), run_time(process.run_time), process.name, process.command)?;
+        }
+        Ok(())
+    }
+
+    pub fn write(&self, format: SnapshotFormat, units: UnitFormat, out: &mut impl Write) -> AppResult<()> {
+        match format {
+            SnapshotFormat::Json => self.write_json(out),
+            SnapshotFormat::Csv => self.write_csv(out),
+            SnapshotFormat::Text => self.write_text(units, out),
+        }
+    }
+}
+
+pub fn run(format: SnapshotFormat, duration: u64, config: Config) -> AppResult<()> {
+    let units = UnitFormat::new(config.units, config.precision);
+    let snapshot = Snapshot::sample(Duration::from_millis(duration), config)?;
+    let mut stdout = std::io::stdout().lock();
+    snapshot.write(format, units, &mut stdout)?;
+    stdout.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_follows_the_documented_schema() {
+        let snapshot = Snapshot {
+            version: SNAPSHOT_VERSION,
+            timestamp: "2024-12-01T10:00:00+03:00".to_string(),
+            host_name: "server01".to_string(),
+            uptime: 12345,
+            cpu: CpuSnapshot { usage: 12.5, cores: vec![10.0, 15.0] },
+            memory: MemorySnapshot::new(16777216, 8388608),
+            swap: MemorySnapshot::new(0, 0),
+            load_average: LoadAverageSnapshot { one: 0.5, five: 0.25, fifteen: 0.125 },
+            processes: vec![ProcessSnapshot {
+                pid: 1,
+                user: "root".to_string(),
+                cpu: 0.0,
+                mem: 0.5,
+                rss: 12582912,
+                run_time: 12345,
+                name: "systemd".to_string(),
+                command: "/usr/lib/systemd/systemd".to_string(),
+            }],
+        };
+        let mut out = Vec::new();
+        snapshot.write(SnapshotFormat::Json, UnitFormat::default(), &mut out).unwrap();
+
+        assert_eq!(serde_json::from_slice::<serde_json::Value>(&out).unwrap(), serde_json::json!({
+            "version": 1,
+            "timestamp": "2024-12-01T10:00:00+03:00",
+            "host_name": "server01",
+            "uptime": 12345,
+            "cpu": { "usage": 12.5, "cores": [10.0, 15.0] },
+            "memory": { "total": 16777216, "used": 8388608, "usage": 50.0 },
+            "swap": { "total": 0, "used": 0, "usage": 0.0 },
+            "load_average": { "one": 0.5, "five": 0.25, "fifteen": 0.125 },
+            "processes": [
+                { "pid": 1, "user": "root", "cpu": 0.0, "mem": 0.5, "rss": 12582912, "run_time": 12345,
+                  "name": "systemd", "command": "/usr/lib/systemd/systemd" }
+            ]
+        }));
+    }
+}
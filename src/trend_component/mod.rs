@@ -1,81 +1,397 @@
 
+use std::borrow::Cow;
+
 use ratatui::buffer::Buffer;
-use ratatui::layout::Rect;
+use ratatui::layout::{Constraint, Rect};
 use ratatui::prelude::{Style};
 use ratatui::style::Color;
 use ratatui::symbols;
-use ratatui::widgets::{Axis, Block, Chart, Dataset, StatefulWidget, Widget};
+use ratatui::symbols::Marker;
+use ratatui::widgets::{Axis, Block, Chart, Dataset, LegendPosition, Widget};
+use crate::units::{Unit, UnitFormat};
+
+pub type TrendPoints = Vec<(f64, f64)>;
+
+// Points of one time bucket reduced to min, max and average
+#[derive(Clone, Copy)]
+pub struct TrendBucket {
+    pub time: f64,
+    pub min: f64,
+    pub max: f64,
+    pub avg: f64,
+}
+
+// Split the time range of points into bucket_count equal buckets, empty buckets are skipped
+pub fn aggregate(points: &[(f64, f64)], bucket_count: usize) -> Vec<TrendBucket> {
+    if points.is_empty() || bucket_count == 0 {
+        return Vec::new();
+    }
+
+    let time_start = points[0].0;
+    let time_end = points[points.len() - 1].0;
+    let bucket_width = (time_end - time_start) / bucket_count as f64;
+
+    let mut buckets = Vec::with_capacity(bucket_count);
+    let mut current: Option<(usize, TrendBucket, usize)> = None;     // (bucket number, bucket, points count)
+
+    for &(time, value) in points {
+        let bucket_num = if bucket_width > 0.0 {
+            (((time - time_start) / bucket_width) as usize).min(bucket_count - 1)
+        } else {
+            0
+        };
+
+        match current.as_mut() {
+            Some((num, bucket, count)) if *num == bucket_num => {
+                bucket.min = bucket.min.min(value);
+                bucket.max = bucket.max.max(value);
+                bucket.avg += value;
+                *count += 1;
+            }
+            _ => {
+                if let Some((_, bucket, count)) = current.take() {
+                    buckets.push(TrendBucket { avg: bucket.avg / count as f64, ..bucket });
+                }
+                let time = time_start + (bucket_num as f64 + 0.5) * bucket_width;
+                current = Some((bucket_num, TrendBucket { time, min: value, max: value, avg: value }, 1));
+            }
+        }
+    }
+
+    if let Some((_, bucket, count)) = current {
+        buckets.push(TrendBucket { avg: bucket.avg / count as f64, ..bucket });
+    }
+    buckets
+}
+
+// Number of intervals between the y axis labels
+const Y_LABEL_INTERVALS: usize = 4;
+// Columns taken by the y axis labels and the borders, roughly
+const Y_AXIS_WIDTH: u16 = 8;
+// Aggregated charts get one bucket per braille dot column
+const BRAILLE_DOTS_PER_COLUMN: usize = 2;
+
+// How the y axis range is chosen
+#[derive(Clone, Copy, PartialEq)]
+pub enum TrendScale {
+    Fixed,      // y_min..y_max as given
+    Fit,        // Range of the visible data
+    Nice,       // From y_min up to the visible data maximum, rounded to nice label steps
+}
 
-pub struct TrendComponent {
-    trend_name: String,
-    trend_color: Color,
+// Step of 1, 2, 2.5 or 5 times a power of ten that splits span into at most intervals parts
+fn nice_step(span: f64, intervals: usize) -> f64 {
+    let raw_step = span / intervals as f64;
+    let magnitude = 10_f64.powf(raw_step.log10().floor());
+    [1.0, 2.0, 2.5, 5.0, 10.0].into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= raw_step)
+        .unwrap_or(10.0 * magnitude)
+}
+
+// Y axis bounds for the scale mode, the data range is (min, max) of the visible points.
+// Integer bounds (counts) get a whole step of at least 1 between the labels.
+fn y_bounds(scale: TrendScale, y_min: f64, y_max: f64, data_range: Option<(f64, f64)>, integer: bool) -> (f64, f64) {
+    let (lower, upper) = scaled_bounds(scale, y_min, y_max, data_range);
+    if !integer {
+        return (lower, upper);
+    }
+    let lower = lower.floor();
+    let step = ((upper - lower) / Y_LABEL_INTERVALS as f64).ceil().max(1.0);
+    (lower, lower + step * Y_LABEL_INTERVALS as f64)
+}
+
+fn scaled_bounds(scale: TrendScale, y_min: f64, y_max: f64, data_range: Option<(f64, f64)>) -> (f64, f64) {
+    let (data_min, data_max) = match (scale, data_range) {
+        (TrendScale::Fixed, _) | (_, None) => return (y_min, y_max),
+        (_, Some(data_range)) => data_range,
+    };
+
+    match scale {
+        TrendScale::Fit => {
+            // Flat data still gets some room around the line
+            let padding = ((data_max - data_min) * 0.05).max(data_max.abs() * 0.05).max(0.5);
+            (data_min - padding, data_max + padding)
+        }
+        _ => {
+            // Flat data at the lower bound (no traffic, no load) keeps the given range
+            let lower = y_min.min(data_min);
+            let upper = if data_max > lower { data_max } else { y_max.max(lower + 1.0) };
+            let step = nice_step(upper - lower, Y_LABEL_INTERVALS);
+            let lower = (lower / step).floor() * step;
+            let upper = (lower + step * Y_LABEL_INTERVALS as f64).max((upper / step).ceil() * step);
+            (lower, upper)
+        }
+    }
+}
+
+// One line of the chart, points are borrowed from the metric history or computed for the frame
+pub struct TrendSeries<'a> {
+    name: String,
+    color: Color,
+    marker: Marker,
+    points: Cow<'a, [(f64, f64)]>,
+}
+
+pub struct TrendComponent<'a> {
+    series: Vec<TrendSeries<'a>>,
     y_max: f64,
     y_min: f64,
     y_title: String,
     x_title: String,
+    scale: TrendScale,
+    unit: Unit,
+    unit_format: UnitFormat,
 
-    trend_value: f64,
-    chart_window: usize,
+    aggregate: bool,
+    stacked: bool,
+    focused: bool,
 }
 
-impl TrendComponent {
+impl<'a> TrendComponent<'a> {
     pub fn new(trend_name: String, trend_color: Color,
                y_max: f64, y_min: f64, y_title: String,
-               x_title: String, chart_window: usize, trend_value: f64) -> Self {
+               x_title: String, trend_points: impl Into<Cow<'a, [(f64, f64)]>>) -> Self {
         Self {
-            trend_name,
-            trend_color,
+            series: vec![TrendSeries {
+                name: trend_name,
+                color: trend_color,
+                marker: Marker::Braille,
+                points: trend_points.into(),
+            }],
             y_max,
             y_min,
             y_title,
             x_title,
+            scale: TrendScale::Fixed,
+            unit: Unit::Plain,
+            unit_format: UnitFormat::default(),
 
-            trend_value,
-            chart_window,
+            aggregate: false,
+            stacked: false,
+            focused: false,
         }
     }
+
+    // Add one more series drawn on the same axes
+    pub fn series(mut self, name: String, color: Color, marker: Marker, points: impl Into<Cow<'a, [(f64, f64)]>>) -> Self {
+        self.series.push(TrendSeries {
+            name,
+            color,
+            marker,
+            points: points.into(),
+        });
+        self
+    }
+
+    pub fn scale(mut self, scale: TrendScale) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    // Unit of the y axis labels
+    pub fn unit(mut self, unit: Unit, unit_format: UnitFormat) -> Self {
+        self.unit = unit;
+        self.unit_format = unit_format;
+        self
+    }
+
+    // Draw the points as min/max/avg buckets fitting the chart width,
+    // used for long histories with more points than the chart has columns
+    pub fn aggregate(mut self, aggregate: bool) -> Self {
+        self.aggregate = aggregate;
+        self
+    }
+
+    // Draw every series on top of the previous ones, the last line is the total
+    pub fn stacked(mut self, stacked: bool) -> Self {
+        self.stacked = stacked;
+        self
+    }
+
+    pub fn focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+}
+
+// Add the values of the series below, points are matched from the end since all series are sampled together
+fn stack<'a>(series_points: &[Cow<'a, [(f64, f64)]>]) -> Vec<Cow<'a, [(f64, f64)]>> {
+    let mut stacked: Vec<Cow<[(f64, f64)]>> = Vec::with_capacity(series_points.len());
+    for points in series_points {
+        let points = match stacked.last() {
+            Some(below) => {
+                let offset = below.len() as isize - points.len() as isize;
+                points.iter().enumerate()
+                    .map(|(i, (time, value))| {
+                        let below_value = usize::try_from(i as isize + offset).ok()
+                            .and_then(|i| below.get(i))
+                            .map_or(0.0, |point| point.1);
+                        (*time, value + below_value)
+                    })
+                    .collect::<TrendPoints>()
+                    .into()
+            }
+            None => points.clone(),
+        };
+        stacked.push(points);
+    }
+    stacked
 }
 
-impl StatefulWidget for TrendComponent {
-    type State = Vec::<(f64, f64)>;
+impl Widget for TrendComponent<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
 
-    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let mut series = self.series;
+        let series_points = series.iter_mut()
+            .map(|series| std::mem::take(&mut series.points))
+            .collect::<Vec<_>>();
+        let series_points = if self.stacked { stack(&series_points) } else { series_points };
 
-        // Update state
-        let tick = state[state.len() - 1].0;
-        if state.len() > self.chart_window {
-            state.remove(0);
-        }
-        state.push((tick + 1_f64, self.trend_value));
+        let bucket_count = area.width.saturating_sub(Y_AXIS_WIDTH) as usize * BRAILLE_DOTS_PER_COLUMN;
+        let aggregated = self.aggregate && series_points.iter().any(|points| points.len() > bucket_count);
+        let buckets = series_points.iter()
+            .map(|points| if aggregated { aggregate(points, bucket_count) } else { Vec::new() })
+            .collect::<Vec<_>>();
+        let avg_points = buckets.iter()
+            .map(|buckets| buckets.iter().map(|bucket| (bucket.time, bucket.avg)).collect::<TrendPoints>())
+            .collect::<Vec<_>>();
+
+        // The min/max envelope is drawn for a single series only, it would hide the others
+        let (max_points, min_points): (TrendPoints, TrendPoints) = match buckets.as_slice() {
+            [buckets] if aggregated => (
+                buckets.iter().map(|bucket| (bucket.time, bucket.max)).collect(),
+                buckets.iter().map(|bucket| (bucket.time, bucket.min)).collect(),
+            ),
+            _ => (Vec::new(), Vec::new()),
+        };
 
-        let database = vec![
-            Dataset::default()
-                .name(self.trend_name)
+        let mut database = Vec::new();
+        let mut database_points: Vec<&[(f64, f64)]> = Vec::new();
+        if !max_points.is_empty() {
+            // Envelope goes first so that the average is drawn over it
+            database.push(Dataset::default()
+                .name("max")
                 .marker(symbols::Marker::Braille)
-                .style(Style::default().fg(self.trend_color))
-                .data(state),
-        ];
+                .style(Style::default().fg(Color::LightRed))
+                .data(&max_points));
+            database.push(Dataset::default()
+                .name("min")
+                .marker(symbols::Marker::Braille)
+                .style(Style::default().fg(Color::DarkGray))
+                .data(&min_points));
+            database_points.extend([max_points.as_slice(), min_points.as_slice()]);
+        }
+        // Stacked series are drawn from the top so that lower lines stay visible
+        let mut series_data = series.iter()
+            .zip(series_points.iter().zip(avg_points.iter()))
+            .map(|(series, (points, avg_points))| (series, if aggregated { avg_points.as_slice() } else { points.as_ref() }))
+            .collect::<Vec<_>>();
+        if self.stacked {
+            series_data.reverse();
+        }
+        for (series, points) in series_data {
+            database.push(Dataset::default()
+                .name(series.name.clone())
+                .marker(series.marker)
+                .style(Style::default().fg(series.color))
+                .data(points));
+            database_points.push(points);
+        }
 
-        let y_center = (self.y_max - self.y_min) / 2.0;
-        let y_1_4 = y_center / 2.0;
-        let y_3_4 = y_center + y_1_4;
+        let x_min = series_points.iter().filter_map(|points| points.first()).map(|point| point.0).fold(f64::INFINITY, f64::min);
+        let x_max = series_points.iter().filter_map(|points| points.last()).map(|point| point.0).fold(f64::NEG_INFINITY, f64::max);
+        let (x_min, x_max) = if x_min <= x_max { (x_min, x_max) } else { (0.0, 0.0) };
+
+        // Value range of the drawn datasets for the automatic scales
+        let data_range = database_points.iter()
+            .flat_map(|points| points.iter())
+            .map(|point| point.1)
+            .fold(None, |range: Option<(f64, f64)>, value| match range {
+                Some((min, max)) => Some((min.min(value), max.max(value))),
+                None => Some((value, value)),
+            });
+        let (y_min, y_max) = y_bounds(self.scale, self.y_min, self.y_max, data_range, self.unit == Unit::Count);
+        let y_labels = (0..=Y_LABEL_INTERVALS)
+            .map(|i| self.unit_format.format(self.unit, y_min + (y_max - y_min) * i as f64 / Y_LABEL_INTERVALS as f64))
+            .collect::<Vec<_>>();
 
         let chart = Chart::new(database)
-            .block(Block::bordered())
+            .block(Block::bordered()
+                .border_style(if self.focused { Style::default().fg(Color::Yellow) } else { Style::default() }))
+            .legend_position(Some(LegendPosition::TopLeft))
+            .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)))
             .x_axis(
                 Axis::default()
                     .title(self.x_title)
                     .style(Style::default().fg(Color::Gray))
-                    .bounds([state[0].0, state[state.len() - 1].0])
+                    .bounds([x_min, x_max])
             )
             .y_axis(
                 Axis::default()
                     .title(self.y_title)
                     .style(Style::default().fg(Color::Gray))
-                    .labels([self.y_min.to_string(), y_1_4.to_string(), y_center.to_string(), y_3_4.to_string(), self.y_max.to_string()])
-                    .bounds([self.y_min, self.y_max]),
+                    .labels(y_labels)
+                    .bounds([y_min, y_max]),
             );
 
         chart.render(area, buf);
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nice_bounds_of_flat_data_at_the_minimum_keep_the_given_range() {
+        assert_eq!(y_bounds(TrendScale::Nice, 0.0, 100.0, Some((0.0, 0.0)), false), (0.0, 100.0));
+    }
+
+    #[test]
+    fn nice_bounds_of_flat_data_without_a_maximum_are_not_empty() {
+        let (lower, upper) = y_bounds(TrendScale::Nice, 0.0, 0.0, Some((0.0, 0.0)), false);
+        assert_eq!(lower, 0.0);
+        assert!(upper >= 1.0);
+    }
+
+    #[test]
+    fn nice_bounds_round_the_data_maximum_up() {
+        assert_eq!(y_bounds(TrendScale::Nice, 0.0, 100.0, Some((3.0, 37.0)), false), (0.0, 40.0));
+    }
+
+    #[test]
+    fn count_bounds_step_by_whole_numbers() {
+        assert_eq!(y_bounds(TrendScale::Nice, 0.0, 10.0, Some((0.0, 1.0)), true), (0.0, 4.0));
+        assert_eq!(y_bounds(TrendScale::Nice, 0.0, 10.0, Some((1.0, 2.0)), true), (0.0, 4.0));
+        assert_eq!(y_bounds(TrendScale::Nice, 0.0, 10.0, Some((0.0, 37.0)), true), (0.0, 40.0));
+        assert_eq!(y_bounds(TrendScale::Fit, 0.0, 10.0, Some((3.0, 3.0)), true), (2.0, 6.0));
+        assert_eq!(y_bounds(TrendScale::Fixed, 0.0, 10.0, None, true), (0.0, 12.0));
+    }
+
+    #[test]
+    fn nice_steps_are_one_two_or_five_times_a_power_of_ten() {
+        assert_eq!(nice_step(100.0, 4), 25.0);
+        assert_eq!(nice_step(37.0, 4), 10.0);
+        assert_eq!(nice_step(0.7, 4), 0.2);
+        assert_eq!(nice_step(9000.0, 4), 2500.0);
+    }
+
+    #[test]
+    fn aggregate_keeps_min_max_and_avg_of_each_bucket() {
+        let points = [(0.0, 1.0), (1.0, 3.0), (2.0, 10.0), (3.0, 0.0), (4.0, 5.0)];
+        let buckets = aggregate(&points, 2);
+
+        assert_eq!(buckets.len(), 2);
+        assert_eq!((buckets[0].time, buckets[0].min, buckets[0].max, buckets[0].avg), (1.0, 1.0, 3.0, 2.0));
+        assert_eq!((buckets[1].time, buckets[1].min, buckets[1].max, buckets[1].avg), (3.0, 0.0, 10.0, 5.0));
+    }
+
+    #[test]
+    fn aggregate_skips_empty_buckets() {
+        let buckets = aggregate(&[(0.0, 1.0), (10.0, 2.0)], 5);
+        assert_eq!(buckets.iter().map(|bucket| bucket.avg).collect::<Vec<_>>(), [1.0, 2.0]);
+        assert!(aggregate(&[], 5).is_empty());
+        assert_eq!(aggregate(&[(1.0, 4.0), (1.0, 6.0)], 5).len(), 1);
+    }
+}
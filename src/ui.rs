@@ -1,27 +1,297 @@
 use ratatui::Frame;
-use ratatui::layout::{Alignment, Constraint, Direction, Layout};
-use ratatui::style::{Color, Modifier, Stylize};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
 use ratatui::style::palette::material::DEEP_ORANGE;
-use ratatui::widgets::{Block, Padding, Paragraph};
+use ratatui::symbols::Marker;
+use ratatui::widgets::{Block, Padding, Paragraph, Tabs};
 use sysinfo::System;
 
-use crate::app::App;
+use crate::alert_log_component::AlertLogComponent;
+use crate::app::{App, Panel, TrendKind, View};
 use crate::bar_component::BarComponent;
+use crate::disk_table_component::DiskTableComponent;
+use crate::help_component::HelpComponent;
+use crate::network_table_component::NetworkTableComponent;
 use crate::proc_table_component::ProcTableComponent;
-use crate::trend_component::TrendComponent;
+use crate::memory_component::MemoryComponent;
+use crate::metric_history::Metric;
+use crate::trend_component::{TrendComponent, TrendPoints, TrendScale};
+use crate::units::Unit;
+use crate::user_table_component::UserTableComponent;
+
+fn trend_component(app: &App, kind: TrendKind) -> TrendComponent<'_> {
+    // Percent trends follow the selected scale, unbounded trends always scale to their data
+    let bounded_scale = app.trend_scale;
+    let unbounded_scale = if app.trend_scale == TrendScale::Fit { TrendScale::Fit } else { TrendScale::Nice };
+
+    let points = |metric: Metric| app.metric_history.get(metric).points();
+    let single = |name: &str, color: Color, y_max: f64, y_title: &str, metric: Metric| {
+        TrendComponent::new(name.to_string(), color, y_max, 0.0, y_title.to_string(),
+                            "s".to_string(), points(metric))
+            .aggregate(app.history_loaded)
+            .unit(Unit::Plain, app.units)
+    };
+
+    match kind {
+        TrendKind::Cpu => single("CPU usage", Color::Cyan, 100.0, "%", Metric::Cpu)
+            .scale(bounded_scale)
+            .unit(Unit::Percent, app.units),
+        TrendKind::Mem => single("MEM usage", DEEP_ORANGE.a200, 100.0, "%", Metric::Mem)
+            .scale(bounded_scale)
+            .unit(Unit::Percent, app.units),
+        TrendKind::Swap => single("SWAP usage", Color::LightMagenta, 100.0, "%", Metric::Swap)
+            .scale(bounded_scale)
+            .unit(Unit::Percent, app.units),
+        TrendKind::Load1 => single("Load 1m", Color::LightGreen, 1.0, "", Metric::Load1)
+            .scale(unbounded_scale),
+        TrendKind::Load5 => single("Load 5m", Color::LightYellow, 1.0, "", Metric::Load5)
+            .scale(unbounded_scale),
+        TrendKind::Load15 => single("Load 15m", Color::LightBlue, 1.0, "", Metric::Load15)
+            .scale(unbounded_scale),
+        TrendKind::Load => single("1m", Color::LightGreen, 1.0, "load", Metric::Load1)
+            .series("5m".to_string(), Color::LightYellow, Marker::Braille, points(Metric::Load5))
+            .series("15m".to_string(), Color::LightBlue, Marker::Braille, points(Metric::Load15))
+            .scale(unbounded_scale),
+        TrendKind::Net => single("RX", Color::LightGreen, 1024.0, "", Metric::NetRx)
+            .series("TX".to_string(), Color::LightRed, Marker::Braille, points(Metric::NetTx))
+            .scale(unbounded_scale)
+            .unit(Unit::BytesPerSec, app.units),
+        TrendKind::Processes => {
+            // Both counts are sampled together, so the points match one to one
+            let other_process_points = points(Metric::Processes).iter()
+                .zip(points(Metric::Running))
+                .map(|(&(time, count), &(_, running))| (time, (count - running).max(0.0)))
+                .collect::<TrendPoints>();
+            single("running", Color::LightCyan, 10.0, "count", Metric::Running)
+                .series("other".to_string(), Color::White, Marker::Braille, other_process_points)
+                .stacked(true)
+                .scale(unbounded_scale)
+                .unit(Unit::Count, app.units)
+        }
+        TrendKind::Running => single("Running", Color::LightCyan, 10.0, "count", Metric::Running)
+            .scale(unbounded_scale)
+            .unit(Unit::Count, app.units),
+    }
+}
+
+// Breakpoints of the responsive layout, the process table always gets the remaining space
+const WIDE_MIN_WIDTH: u16 = 100;            // Vertical bars and the full info band
+const FULL_INFO_MIN_HEIGHT: u16 = 36;       // The full info band takes 6 rows, one line otherwise
+const TRENDS_MIN_HEIGHT: u16 = 24;          // Trends are hidden in lower data areas
+const TREND_MIN_WIDTH: u16 = 30;            // Trends that don't fit are dropped from the right
+const HORIZONTAL_BAR_HEIGHT: u16 = 5;
+const COMPACT_BARS_MAX_HEIGHT: u16 = 30;    // Narrow terminals lower than this get one line bars
+const ALERT_LOG_HEIGHT: u16 = 10;
+const MEMORY_PANEL_HEIGHT: u16 = 14;         // Bars and the readout of the Memory view
 
 // todo - перенести виджеты в отдельную папку
 pub(crate) fn draw(frame: &mut Frame, app: &mut App) {
 
     // --------------- Components --------------- //
 
-    let title_string = Paragraph::new(
-        "SYSTEM LOAD MANAGER")
+    // Fired alerts are shown in the title
+    let active_alerts = app.alert_manager.active()
+        .map(|entry| entry.name.as_str())
+        .collect::<Vec<_>>();
+    let (title, title_color) = if active_alerts.is_empty() {
+        ("SYSTEM LOAD MANAGER".to_string(), Color::Cyan)
+    } else {
+        (format!("SYSTEM LOAD MANAGER - ALERT: {}", active_alerts.join(", ")), Color::Red)
+    };
+
+    let title_string = Paragraph::new(title)
         .alignment(Alignment::Center)
+        .bg(title_color)
+        .fg(Color::Rgb(0, 0, 0))
+        .add_modifier(Modifier::BOLD);
+
+    // Create tab bar, the process views show the user and thread filters
+    let process_table_filter = app.process_table_filter();
+    let tabs = Tabs::new(View::ALL.iter().enumerate().map(|(i, view)| match &process_table_filter {
+        Some(filter) if view.has_process_table() => format!("F{} {} ({filter})", i + 2, view.title()),
+        _ => format!("F{} {}", i + 2, view.title()),
+    }))
+        .select(app.view.index())
+        .style(Style::new().fg(Color::Gray))
+        .highlight_style(Style::new().fg(Color::Rgb(0, 0, 0)).bg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .divider("|");
+
+    // Create info string
+    let info_string = Paragraph::new(
+        app.info_string.to_owned())
+        .alignment(Alignment::Center)
+        .bg(Color::Cyan)
+        .fg(Color::Rgb(0, 0, 0))
+        .add_modifier(Modifier::BOLD);
+
+    // Create clock
+    let clock_string = Paragraph::new(
+        app.clock.format("%H:%M:%S").to_string())
+        .alignment(Alignment::Left)
         .bg(Color::Cyan)
         .fg(Color::Rgb(0, 0, 0))
         .add_modifier(Modifier::BOLD);
 
+
+    // --------------- Layouts --------------- //
+
+    // area layout
+    let [title_layout, tabs_layout, view_layout, bottom_layout] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Length(1),
+        Constraint::Fill(1),
+        Constraint::Length(1),
+    ])
+        .areas(frame.area());
+
+    // bottom_layout
+    let [info_bottom_layout, clock_bottom_layout] = Layout::horizontal([
+        Constraint::Fill(1),
+        Constraint::Length(10),
+    ])
+        .areas(bottom_layout);
+
+
+    // --------------- Rendering --------------- //
+
+    // Render title string
+    frame.render_widget(title_string, title_layout);
+
+    // Render tab bar
+    frame.render_widget(tabs, tabs_layout);
+
+    // Render bottom info
+    frame.render_widget(info_string, info_bottom_layout);
+    // Render clock
+    frame.render_widget(clock_string, clock_bottom_layout);
+
+    // Lay out the current view, the overview and history headers are rendered here too
+    let (data_layout, panel_layouts) = match app.view {
+        View::Overview => overview_layout(frame, app, view_layout),
+        View::Processes => (view_layout, process_table_layout(app, view_layout)),
+        View::Network => (view_layout, network_layout(view_layout)),
+        View::Users => (view_layout, vec![(Panel::UserTable, view_layout)]),
+        View::Memory => (view_layout, memory_layout(view_layout)),
+        View::Disks => (view_layout, vec![(Panel::DiskTable, view_layout)]),
+        View::History => history_layout(frame, app, view_layout),
+    };
+
+    // The zoomed panel takes the whole data area of the view
+    app.panel_order = panel_layouts.iter().map(|(panel, _)| *panel).collect();
+    let focused_panel = app.focused_panel();
+    app.panel_areas = if app.is_zoomed() && app.panel_order.contains(&focused_panel) {
+        vec![(focused_panel, data_layout)]
+    } else {
+        panel_layouts
+    };
+
+    // Render panels
+    for (panel, panel_layout) in app.panel_areas.clone() {
+        draw_panel(frame, app, panel, panel_layout, panel == focused_panel);
+    }
+
+    // Help overlay on top of everything
+    if app.help_visible {
+        frame.render_stateful_widget(HelpComponent, frame.area(), &mut app.help_scroll);
+    }
+}
+
+// Low narrow terminals get one line bars
+fn compact_bars(area: Rect) -> bool {
+    area.width < WIDE_MIN_WIDTH && area.height < COMPACT_BARS_MAX_HEIGHT
+}
+
+fn draw_panel(frame: &mut Frame, app: &mut App, panel: Panel, area: Rect, focused: bool) {
+    // Narrow terminals get horizontal bars
+    let bar_direction = if frame.area().width >= WIDE_MIN_WIDTH { Direction::Vertical } else { Direction::Horizontal };
+    let compact_bars = compact_bars(frame.area());
+    let bar = |name: &str, value: f64, max_value: f64| BarComponent::new(name.to_string(), value, max_value)
+        .unit(Unit::Percent, app.units)
+        .direction(bar_direction)
+        .compact(compact_bars)
+        .focused(focused);
+
+    match panel {
+        Panel::CpuBar => frame.render_widget(bar(" CPU ", app.cpu_usage_human as f64, 100.0).levels(50.0, 80.0), area),
+        Panel::MemBar => frame.render_widget(bar(" MEM ", app.usage_mem_per, app.total_mem_per).levels(70.0, 90.0), area),
+        Panel::SwapBar => frame.render_widget(bar(" SWAP ", app.usage_swap_per, app.total_swap_per).levels(40.0, 70.0), area),
+        Panel::Trend(kind) => {
+            // The History view always shows the whole history
+            let trend = trend_component(app, kind).focused(focused);
+            let trend = if app.view == View::History { trend.aggregate(true) } else { trend };
+            frame.render_widget(trend, area);
+        }
+        Panel::ProcessTrend => draw_process_trend(frame, app, area, focused),
+        Panel::ProcessTable => {
+            let proc_table = ProcTableComponent::new(&app.process_table_items_vec, &app.process_table_sort_param, app.units)
+                .group_by(app.process_group_by)
+                .thread_view(app.process_thread_view.is_some())
+                .focused(focused);
+            frame.render_stateful_widget(proc_table, area, &mut app.process_table_state);
+        }
+        Panel::AlertLog => {
+            let alert_log = AlertLogComponent::new(&app.alert_manager.log, app.units).focused(focused);
+            frame.render_stateful_widget(alert_log, area, &mut app.alert_log_state);
+        }
+        Panel::NetworkTable => {
+            let network_table = NetworkTableComponent::new(&app.network_interfaces, app.units).focused(focused);
+            frame.render_stateful_widget(network_table, area, &mut app.network_table_state);
+        }
+        Panel::UserTable => {
+            let user_table = UserTableComponent::new(&app.user_stats.users, app.user_table_sort_param, app.units).focused(focused);
+            frame.render_stateful_widget(user_table, area, &mut app.user_table_state);
+        }
+        Panel::Memory => frame.render_widget(MemoryComponent::new(&app.memory, app.units).focused(focused), area),
+        Panel::DiskTable => {
+            let disk_table = DiskTableComponent::new(app.disks.list(), app.units).focused(focused);
+            frame.render_stateful_widget(disk_table, area, &mut app.disk_table_state);
+        }
+    }
+}
+
+// Selected process trends, CPU% and RSS
+fn draw_process_trend(frame: &mut Frame, app: &App, area: Rect, focused: bool) {
+    let (process_name, process_cpu_points, process_rss_points): (String, TrendPoints, TrendPoints) = match app.selected_process() {
+        Some(process) => match app.process_history.get(process.pid()) {
+            Some(history) => (process.name().to_string(), history.cpu.clone(), history.rss.clone()),
+            None => (process.name().to_string(), Vec::new(), Vec::new()),
+        },
+        None => ("".to_string(), Vec::new(), Vec::new()),
+    };
+    let process_cpu_trend = TrendComponent::new(format!("{process_name} CPU"),
+                                                Color::Cyan, 100.0, 0.0, "".to_string(),
+                                                "tick".to_string(), process_cpu_points)
+        .scale(TrendScale::Nice)
+        .unit(Unit::Percent, app.units)
+        .focused(focused);
+
+    let process_rss_trend = TrendComponent::new(format!("{process_name} RSS"),
+                                                DEEP_ORANGE.a200, 1024.0 * 1024.0, 0.0, "".to_string(),
+                                                "tick".to_string(), process_rss_points)
+        .scale(TrendScale::Nice)
+        .unit(Unit::Bytes, app.units)
+        .focused(focused);
+
+    let [process_cpu_trend_layout, process_rss_trend_layout] = Layout::horizontal([
+        Constraint::Percentage(50),
+        Constraint::Percentage(50),
+    ])
+        .areas(area);
+
+    frame.render_widget(process_cpu_trend, process_cpu_trend_layout);
+    frame.render_widget(process_rss_trend, process_rss_trend_layout);
+}
+
+// System info band (rendered here), then bars, trends and the process table.
+// Returns the data area under the band and the panels in focus order.
+fn overview_layout(frame: &mut Frame, app: &App, area: Rect) -> (Rect, Vec<(Panel, Rect)>) {
+
+    // Narrow terminals get horizontal bars and a one line info band
+    let wide = frame.area().width >= WIDE_MIN_WIDTH;
+    let full_info = wide && frame.area().height >= FULL_INFO_MIN_HEIGHT;
+
+    // --------------- Components --------------- //
+
     let system_info_left = Paragraph::new(
         format!("CPU arch: {}\nHost name: {}\nKernel version: {}\nOS version: {}",
                 System::cpu_arch().unwrap(), System::host_name().unwrap(),
@@ -30,80 +300,48 @@ pub(crate) fn draw(frame: &mut Frame, app: &mut App) {
         .fg(Color::Yellow)
         .block(Block::new().padding(Padding::new(1, 0 ,1, 1)));
 
+    let bytes = |value: u64| app.units.format(Unit::Bytes, value as f64);
+    let load = |value: f64| app.units.number(value);
+
     let system_info_center = Paragraph::new(
-        format!("Load CPU: {:.2}\nSystem uptime: {}\nLoad average: {:.2} {:.2} {:.2}\nMemory: {:.6}GB / {}GB",
-                app.cpu_usage_human, app.system_uptime,
-                System::load_average().one, System::load_average().five,
-                System::load_average().fifteen, app.usage_mem, app.total_mem))
+        format!("Load CPU: {}\nSystem uptime: {}\nLoad average: {} {} {}\nMemory: {} / {}",
+                app.units.format(Unit::Percent, app.cpu_usage_human as f64), app.system_uptime,
+                load(app.load_average_one), load(app.load_average_five),
+                load(app.load_average_fifteen), bytes(app.usage_mem), bytes(app.total_mem)))
         .alignment(Alignment::Left)
         .fg(Color::Yellow)
         .block(Block::new().padding(Padding::new(1, 0 ,1, 1)));
 
     let system_info_right = Paragraph::new(
-        format!("Swap: {:.6}GB / {:.4}GB",
-                app.usage_swap, app.total_swap))
+        format!("Swap: {} / {}{}",
+                bytes(app.usage_swap), bytes(app.total_swap),
+                match &app.history_host_name {
+                    Some(host_name) => format!("\nHistory host: {host_name}"),
+                    None => "".to_string(),
+                }))
         .alignment(Alignment::Left)
         .fg(Color::Yellow)
         .block(Block::new().padding(Padding::new(1, 0 ,1, 1)));
 
-    // Create CPU vertical widget
-    let cpu_bar = BarComponent::new(" CPU ".to_string(),
-                                    app.cpu_usage_human, 100.0,
-                                    50.0, 80.0, "".to_string(),
-                                    Direction::Vertical, Alignment::Center);
-
-    // Create MEM widget
-    let mem_bar = BarComponent::new(" MEM ".to_string(),
-                                    app.usage_mem_per as f32, 100.0,
-                                    70.0, 90.0, "".to_string(),
-                                    Direction::Vertical, Alignment::Center);
-
-    // Create SWAP widget
-    let swap_bar = BarComponent::new(" SWAP ".to_string(),
-                                     app.usage_swap_per as f32, app.total_swap_per,
-                                     40.0, 70.0, "".to_string(),
-                                     Direction::Vertical, Alignment::Center);
-
-    // Create info string
-    let info_string = Paragraph::new(
-        app.info_string.to_owned())
-        .alignment(Alignment::Center)
-        .bg(Color::Cyan)
-        .fg(Color::Rgb(0, 0, 0))
-        .add_modifier(Modifier::BOLD);
-
-    // Create CPU usage trend
-    let cpu_usage_trend = TrendComponent::new("CPU usage".to_string(),
-                                              Color::Cyan, 100.0, 0.0, "%".to_string(),
-                                              "tick".to_string(), 500, app.cpu_usage_human as f64);
-
-    // Create MEM usage trend
-    let mem_usage_trend = TrendComponent::new("MEM usage".to_string(),
-                                              DEEP_ORANGE.a200, 100.0, 0.0, "%".to_string(),
-                                              "tick".to_string(), 500, app.usage_mem_per as f64);
-
-    // Create clock
-    let clock_string = Paragraph::new(
-        format!("{}", app.clock.format("%H:%M:%S").to_string()))
+    // One line info band of narrow or low terminals
+    let system_info_compact = Paragraph::new(
+        format!("CPU: {} | Load: {} {} {} | Mem: {}/{} | Swap: {}/{} | Uptime: {}",
+                app.units.format(Unit::Percent, app.cpu_usage_human as f64),
+                load(app.load_average_one), load(app.load_average_five), load(app.load_average_fifteen),
+                bytes(app.usage_mem), bytes(app.total_mem), bytes(app.usage_swap), bytes(app.total_swap),
+                app.system_uptime))
         .alignment(Alignment::Left)
-        .bg(Color::Cyan)
-        .fg(Color::Rgb(0, 0, 0))
-        .add_modifier(Modifier::BOLD);
-
-    // Create process table
-    let proc_table = ProcTableComponent::new(&app.process_table_items_vec, &app.process_table_sort_param);
+        .fg(Color::Yellow);
 
 
     // --------------- Layouts --------------- //
 
     // area layout
-    let [title_layout, system_info_layout, data_layout, bottom_layout] = Layout::vertical([
-        Constraint::Length(1),
-        Constraint::Length(6),
+    let [system_info_layout, data_layout] = Layout::vertical([
+        Constraint::Length(if full_info { 6 } else { 1 }),
         Constraint::Fill(1),
-        Constraint::Length(1),
     ])
-        .areas(frame.area());
+        .areas(area);
 
     // system_info_layout
     let [system_info_left_layout, system_info_center_layout, system_info_right_layout] = Layout::horizontal([
@@ -114,70 +352,140 @@ pub(crate) fn draw(frame: &mut Frame, app: &mut App) {
         .spacing(1)
         .areas(system_info_layout);
 
-    // data_layout
-    let [cpu_usage_bar_layout, mem_usage_bar_layout, swap_usage_bar_layout, data_right_layout] = Layout::horizontal([
-        Constraint::Length(14),
-        Constraint::Length(14),
-        Constraint::Length(14),
-        Constraint::Percentage(100),
-    ])
-        .spacing(1)
-        .areas(data_layout);
-
-    // data_right_layout
+    // data_layout, the bars are a column on the left of wide terminals and a row on top of narrow ones
+    let ([cpu_usage_bar_layout, mem_usage_bar_layout, swap_usage_bar_layout], data_right_layout) = if wide {
+        let [cpu_usage_bar_layout, mem_usage_bar_layout, swap_usage_bar_layout, data_right_layout] = Layout::horizontal([
+            Constraint::Length(14),
+            Constraint::Length(14),
+            Constraint::Length(14),
+            Constraint::Percentage(100),
+        ])
+            .spacing(1)
+            .areas(data_layout);
+        ([cpu_usage_bar_layout, mem_usage_bar_layout, swap_usage_bar_layout], data_right_layout)
+    } else {
+        let [bars_layout, data_right_layout] = Layout::vertical([
+            Constraint::Length(if compact_bars(frame.area()) { 1 } else { HORIZONTAL_BAR_HEIGHT }),
+            Constraint::Fill(1),
+        ])
+            .areas(data_layout);
+        (Layout::horizontal([Constraint::Fill(1); 3]).spacing(1).areas(bars_layout), data_right_layout)
+    };
+
+    // data_right_layout, trends are hidden when the table would get too few rows
+    let trends_visible = data_right_layout.height >= TRENDS_MIN_HEIGHT;
     let [top_data_right_layout, proc_table_layout] = Layout::vertical([
-        Constraint::Percentage(33),
+        Constraint::Percentage(if trends_visible { 33 } else { 0 }),
         Constraint::Fill(1),
     ])
         .areas(data_right_layout);
 
-    // top_data_right_layout
-    let [cpu_usage_trend_layout, mem_usage_trend_layout] = Layout::horizontal([
-        Constraint::Percentage(50),
-        Constraint::Percentage(50),
-    ])
-        .areas(top_data_right_layout);
-
-    // bottom_layout
-    let [info_bottom_layout, clock_bottom_layout] = Layout::horizontal([
-        Constraint::Fill(1),
-        Constraint::Length(10),
-    ])
-        .areas(bottom_layout);
-
-
+    // top_data_right_layout, the selected process trends take the last trend place
+    let max_trend_count = match trends_visible {
+        true => (top_data_right_layout.width / TREND_MIN_WIDTH).max(1) as usize,
+        false => 0,
+    };
+    let trend_count = if app.process_trend_visible { app.trends.len().max(1) } else { app.trends.len() }
+        .min(max_trend_count);
+    let trend_layouts = Layout::horizontal(vec![Constraint::Fill(1); trend_count])
+        .split(top_data_right_layout);
+    let shown_trend_count = if app.process_trend_visible { trend_count.saturating_sub(1) } else { trend_count };
 
 
     // --------------- Rendering --------------- //
 
     // Render system info
-    frame.render_widget(system_info_left, system_info_left_layout);
-    frame.render_widget(system_info_center, system_info_center_layout);
-    frame.render_widget(system_info_right, system_info_right_layout);
-
-    // Render CPU usage bar
-    frame.render_widget(cpu_bar, cpu_usage_bar_layout);
-
-    // Render MEM usage bar
-    frame.render_widget(mem_bar, mem_usage_bar_layout);
-
-    // Render SWAP usage bar
-    // frame.render_widget(swap_bar_hor, swap_usage_bar_layout_hor);
-    frame.render_widget(swap_bar, swap_usage_bar_layout);
+    if full_info {
+        frame.render_widget(system_info_left, system_info_left_layout);
+        frame.render_widget(system_info_center, system_info_center_layout);
+        frame.render_widget(system_info_right, system_info_right_layout);
+    } else {
+        frame.render_widget(system_info_compact, system_info_layout);
+    }
+
+    // Panels
+    let mut panels = vec![
+        (Panel::CpuBar, cpu_usage_bar_layout),
+        (Panel::MemBar, mem_usage_bar_layout),
+        (Panel::SwapBar, swap_usage_bar_layout),
+    ];
+    panels.extend(app.trends.iter().zip(trend_layouts.iter()).take(shown_trend_count)
+        .map(|(kind, trend_layout)| (Panel::Trend(*kind), *trend_layout)));
+    if app.process_trend_visible {
+        if let Some(last_trend_layout) = trend_layouts.last() {
+            panels.push((Panel::ProcessTrend, *last_trend_layout));
+        }
+    }
+    panels.extend(process_table_layout(app, proc_table_layout));
+    (data_layout, panels)
+}
+
+// Process table, the alert log takes the bottom of the area when shown, at most a half
+fn process_table_layout(app: &App, area: Rect) -> Vec<(Panel, Rect)> {
+    if !app.alert_log_visible {
+        return vec![(Panel::ProcessTable, area)];
+    }
+
+    let [proc_table_layout, alert_log_layout] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(ALERT_LOG_HEIGHT.min(area.height / 2)),
+    ])
+        .areas(area);
+    vec![(Panel::ProcessTable, proc_table_layout), (Panel::AlertLog, alert_log_layout)]
+}
+
+// Network trend and the interface table
+fn network_layout(area: Rect) -> Vec<(Panel, Rect)> {
+    let [net_trend_layout, network_table_layout] = Layout::vertical([
+        Constraint::Percentage(40),
+        Constraint::Fill(1),
+    ])
+        .areas(area);
+    vec![(Panel::Trend(TrendKind::Net), net_trend_layout), (Panel::NetworkTable, network_table_layout)]
+}
+
+// Memory breakdown on top, MEM and SWAP trends under it
+fn memory_layout(area: Rect) -> Vec<(Panel, Rect)> {
+    let [memory_panel_layout, trends_layout] = Layout::vertical([
+        Constraint::Length(MEMORY_PANEL_HEIGHT),
+        Constraint::Fill(1),
+    ])
+        .areas(area);
+    let [mem_trend_layout, swap_trend_layout] = Layout::horizontal([Constraint::Fill(1); 2]).areas(trends_layout);
+    vec![
+        (Panel::Memory, memory_panel_layout),
+        (Panel::Trend(TrendKind::Mem), mem_trend_layout),
+        (Panel::Trend(TrendKind::Swap), swap_trend_layout),
+    ]
+}
+
+// Whole metric history, including a loaded history file, on full size trends.
+// The info line is rendered here, the trends are the panels under it.
+fn history_layout(frame: &mut Frame, app: &App, area: Rect) -> (Rect, Vec<(Panel, Rect)>) {
+    let history_info = match &app.history_host_name {
+        Some(host_name) => format!("History file recorded on {host_name}, followed by the live session"),
+        None if app.history_loaded => "History file, followed by the live session".to_string(),
+        None => "Live session, start with -d to load a history file".to_string(),
+    };
+    let history_info = Paragraph::new(history_info)
+        .alignment(Alignment::Left)
+        .fg(Color::Yellow);
 
-    // Render title string
-    frame.render_widget(title_string, title_layout);
+    let [history_info_layout, trends_layout] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Fill(1),
+    ])
+        .areas(area);
+    let trend_rows: [Rect; 2] = Layout::vertical([Constraint::Fill(1); 2]).areas(trends_layout);
+    let trend_layouts = trend_rows.iter()
+        .flat_map(|row| Layout::horizontal([Constraint::Fill(1); 3]).split(*row).to_vec());
 
-    // Render CPU usage trend
-    frame.render_stateful_widget(cpu_usage_trend, cpu_usage_trend_layout, &mut app.cpu_usage_trend_vec);
-    // Render MEM usage trend
-    frame.render_stateful_widget(mem_usage_trend, mem_usage_trend_layout, &mut app.mem_usage_trend_vec);
+    frame.render_widget(history_info, history_info_layout);
 
-    // Render bottom info
-    frame.render_widget(info_string, info_bottom_layout);
-    // Render clock
-    frame.render_widget(clock_string, clock_bottom_layout);
+    let trend_kinds = [TrendKind::Cpu, TrendKind::Mem, TrendKind::Swap, TrendKind::Load, TrendKind::Net, TrendKind::Processes];
+    let panels = trend_kinds.into_iter().zip(trend_layouts)
+        .map(|(kind, trend_layout)| (Panel::Trend(kind), trend_layout))
+        .collect();
+    (trends_layout, panels)
+}
 
-    // Render process table
-    frame.render_stateful_widget(proc_table, proc_table_layout, &mut app.process_table_state);
-}
\ No newline at end of file
@@ -0,0 +1,145 @@
+use serde::Deserialize;
+
+// Decimals of formatted values, trailing zeros are dropped
+pub const DEFAULT_PRECISION: usize = 2;
+pub const MAX_PRECISION: usize = 6;
+
+const BINARY_UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+const SI_UNITS: [&str; 5] = ["B", "kB", "MB", "GB", "TB"];
+
+// Byte multiples
+#[derive(Clone, Copy, PartialEq, Default, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum UnitSystem {
+    #[default]
+    Binary,     // KiB, MiB, GiB, TiB: powers of 1024
+    Si,         // kB, MB, GB, TB: powers of 1000
+}
+
+// What a value is, chooses the suffix and the scaling
+#[derive(Clone, Copy, PartialEq)]
+pub enum Unit {
+    Plain,
+    Percent,
+    Bytes,
+    BytesPerSec,
+    Count,
+}
+
+// Process run time as hours:minutes:seconds, the hours are not split into days
+pub fn run_time(seconds: u64) -> String {
+    format!("{}:{}:{}", seconds / 3600, seconds % 3600 / 60, seconds % 60)
+}
+
+// Formatting of values for people, the TUI and the text outputs. Machine outputs (JSON, CSV,
+// Prometheus) keep the raw base units.
+#[derive(Clone, Copy)]
+pub struct UnitFormat {
+    system: UnitSystem,
+    precision: usize,
+}
+
+impl Default for UnitFormat {
+    fn default() -> Self {
+        Self::new(UnitSystem::Binary, DEFAULT_PRECISION)
+    }
+}
+
+impl UnitFormat {
+    pub fn new(system: UnitSystem, precision: usize) -> Self {
+        Self {
+            system,
+            precision: precision.min(MAX_PRECISION),
+        }
+    }
+
+    // Width of formatted values with up to 4 integer digits, for table columns
+    pub fn width(&self, unit: Unit) -> usize {
+        let decimals = if self.precision > 0 { self.precision + 1 } else { 0 };
+        match unit {
+            Unit::Plain => 4 + decimals,
+            Unit::Percent => 5 + decimals,
+            Unit::Bytes => 7 + decimals,
+            Unit::BytesPerSec => 9 + decimals,
+            Unit::Count => 4,
+        }
+    }
+
+    pub fn format(&self, unit: Unit, value: f64) -> String {
+        match unit {
+            Unit::Plain => self.number(value),
+            Unit::Percent => format!("{}%", self.number(value)),
+            Unit::Bytes => self.bytes(value),
+            Unit::BytesPerSec => format!("{}/s", self.bytes(value)),
+            Unit::Count => format!("{}", value.round()),
+        }
+    }
+
+    // Up to precision decimals without trailing zeros
+    pub fn number(&self, value: f64) -> String {
+        let value = format!("{value:.*}", self.precision);
+        if value.contains('.') {
+            value.trim_end_matches('0').trim_end_matches('.').to_string()
+        } else {
+            value
+        }
+    }
+
+    // Auto-scaled to the largest unit below the value, whole bytes stay integers
+    fn bytes(&self, value: f64) -> String {
+        let (base, units) = match self.system {
+            UnitSystem::Binary => (1024.0, BINARY_UNITS),
+            UnitSystem::Si => (1000.0, SI_UNITS),
+        };
+        let mut value = value;
+        let mut unit = 0;
+        while value.abs() >= base && unit < units.len() - 1 {
+            value /= base;
+            unit += 1;
+        }
+        if unit == 0 {
+            format!("{}{}", value.round(), units[unit])
+        } else {
+            format!("{}{}", self.number(value), units[unit])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_drop_trailing_zeros() {
+        let units = UnitFormat::new(UnitSystem::Binary, 2);
+        assert_eq!(units.number(12.345), "12.35");
+        assert_eq!(units.number(12.5), "12.5");
+        assert_eq!(units.number(12.0), "12");
+        assert_eq!(UnitFormat::new(UnitSystem::Binary, 0).number(12.5), "12");
+    }
+
+    #[test]
+    fn precision_is_capped() {
+        assert_eq!(UnitFormat::new(UnitSystem::Binary, 10).number(1.0 / 3.0), "0.333333");
+    }
+
+    #[test]
+    fn bytes_scale_to_the_unit_system() {
+        let binary = UnitFormat::new(UnitSystem::Binary, 2);
+        let si = UnitFormat::new(UnitSystem::Si, 2);
+        assert_eq!(binary.format(Unit::Bytes, 1023.0), "1023B");
+        assert_eq!(binary.format(Unit::Bytes, 1536.0), "1.5KiB");
+        assert_eq!(si.format(Unit::Bytes, 1536.0), "1.54kB");
+        assert_eq!(binary.format(Unit::Bytes, 3.0 * 1024_f64.powi(3)), "3GiB");
+        assert_eq!(binary.format(Unit::BytesPerSec, 2048.0), "2KiB/s");
+        // Past the largest unit the number grows
+        assert_eq!(si.format(Unit::Bytes, 2e15), "2000TB");
+    }
+
+    #[test]
+    fn run_time_keeps_the_hours_whole() {
+        assert_eq!(run_time(59), "0:0:59");
+        assert_eq!(run_time(3 * 3600 + 5 * 60 + 7), "3:5:7");
+        assert_eq!(run_time(50 * 3600), "50:0:0");
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
+use sysinfo::{Pid, Process};
+
+use crate::user_table_component::UserSortParam;
+
+// Resources of all processes of one user
+pub struct UserStats {
+    pub user: String,
+    pub processes: usize,
+    pub cpu: f64,           // Summed process CPU%, like the process table
+    pub mem: u64,           // Summed RSS, bytes
+    pub mem_per: f64,       // % of total memory
+    pub read_rate: f64,     // bytes/s
+    pub write_rate: f64,    // bytes/s
+}
+
+impl UserStats {
+    fn new(user: String) -> Self {
+        Self {
+            user,
+            processes: 0,
+            cpu: 0.0,
+            mem: 0,
+            mem_per: 0.0,
+            read_rate: 0.0,
+            write_rate: 0.0,
+        }
+    }
+}
+
+// Per-user totals for the Users view. I/O rates are taken from the process disk counters
+// of the previous update, processes seen for the first time count from the next one.
+#[derive(Default)]
+pub struct UserStatsStore {
+    time: Option<Instant>,
+    io_totals: HashMap<Pid, (u64, u64)>,       // Read and written bytes
+    pub users: Vec<UserStats>,
+}
+
+impl UserStatsStore {
+    pub fn update(&mut self, processes: &HashMap<Pid, Process>, total_memory: u64, is_thread: fn(&Process) -> bool,
+                  user_name: impl Fn(&Process) -> String, sort_param: UserSortParam) {
+
+        let elapsed = self.time.map(|time| time.elapsed().as_secs_f64().max(f64::EPSILON));
+        self.time = Some(Instant::now());
+
+        let mut users = HashMap::<String, UserStats>::new();
+        let mut io_totals = HashMap::with_capacity(processes.len());
+        for (pid, process) in processes.iter().filter(|(_, process)| !is_thread(process)) {
+            let name = user_name(process);
+            let stats = users.entry(name.clone()).or_insert_with(|| UserStats::new(name));
+            stats.processes += 1;
+            stats.cpu += process.cpu_usage() as f64;
+            stats.mem += process.memory();
+
+            let disk_usage = process.disk_usage();
+            let totals = (disk_usage.total_read_bytes, disk_usage.total_written_bytes);
+            if let (Some(elapsed), Some(previous)) = (elapsed, self.io_totals.get(pid)) {
+                stats.read_rate += totals.0.saturating_sub(previous.0) as f64 / elapsed;
+                stats.write_rate += totals.1.saturating_sub(previous.1) as f64 / elapsed;
+            }
+            io_totals.insert(*pid, totals);
+        }
+        self.io_totals = io_totals;
+
+        self.users = users.into_values()
+            .map(|stats| UserStats {
+                mem_per: if total_memory == 0 { 0.0 } else { stats.mem as f64 / total_memory as f64 * 100.0 },
+                ..stats
+            })
+            .collect();
+        self.sort(sort_param);
+    }
+
+    // Name ascending, the values descending
+    pub fn sort(&mut self, sort_param: UserSortParam) {
+        match sort_param {
+            UserSortParam::User => self.users.sort_by(|a, b| a.user.cmp(&b.user)),
+            UserSortParam::Processes => self.users.sort_by_key(|stats| std::cmp::Reverse(stats.processes)),
+            UserSortParam::Cpu => self.users.sort_by(|a, b| b.cpu.total_cmp(&a.cpu)),
+            UserSortParam::Mem => self.users.sort_by_key(|stats| std::cmp::Reverse(stats.mem)),
+            UserSortParam::Io => self.users.sort_by(|a, b| (b.read_rate + b.write_rate).total_cmp(&(a.read_rate + a.write_rate))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sysinfo::{ProcessesToUpdate, System};
+
+    fn not_thread(_: &Process) -> bool {
+        false
+    }
+
+    fn stats(user: &str, processes: usize, cpu: f64, mem: u64, io: f64) -> UserStats {
+        UserStats { processes, cpu, mem, read_rate: io, ..UserStats::new(user.to_string()) }
+    }
+
+    #[test]
+    fn totals_are_summed_per_user_and_replaced_on_update() {
+        let mut system = System::new();
+        system.refresh_processes(ProcessesToUpdate::All, true);
+        let processes = system.processes();
+        let own_pid = sysinfo::get_current_pid().unwrap();
+        let user_name = |process: &Process| if process.pid() == own_pid { "me" } else { "others" }.to_string();
+
+        let mut store = UserStatsStore::default();
+        for _ in 0..2 {
+            store.update(processes, 1 << 40, not_thread, user_name, UserSortParam::User);
+            assert_eq!(store.users.iter().map(|stats| stats.user.as_str()).collect::<Vec<_>>(), ["me", "others"]);
+            assert_eq!(store.users[0].processes, 1);
+            assert_eq!(store.users[0].mem, processes[&own_pid].memory());
+            assert_eq!(store.users.iter().map(|stats| stats.processes).sum::<usize>(), processes.len());
+            assert_eq!(store.users.iter().map(|stats| stats.mem).sum::<u64>(),
+                       processes.values().map(Process::memory).sum::<u64>());
+            assert_eq!(store.users[0].mem_per, processes[&own_pid].memory() as f64 / (1u64 << 40) as f64 * 100.0);
+        }
+        assert_eq!(store.io_totals.len(), processes.len());
+
+        // Gone processes are dropped with their disk counters
+        store.update(&HashMap::new(), 1 << 40, not_thread, user_name, UserSortParam::User);
+        assert!(store.users.is_empty());
+        assert!(store.io_totals.is_empty());
+    }
+
+    #[test]
+    fn first_update_has_no_io_rates() {
+        let mut system = System::new();
+        system.refresh_processes(ProcessesToUpdate::All, true);
+        let mut store = UserStatsStore::default();
+        store.update(system.processes(), 0, not_thread, |_| "user".to_string(), UserSortParam::Io);
+        assert_eq!((store.users[0].read_rate, store.users[0].write_rate, store.users[0].mem_per), (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn threads_are_not_counted() {
+        let mut system = System::new();
+        system.refresh_processes(ProcessesToUpdate::All, true);
+        let mut store = UserStatsStore::default();
+        store.update(system.processes(), 0, |_| true, |_| "user".to_string(), UserSortParam::User);
+        assert!(store.users.is_empty());
+    }
+
+    #[test]
+    fn users_sort_by_name_ascending_and_values_descending() {
+        let mut store = UserStatsStore {
+            users: vec![stats("bob", 1, 50.0, 10, 0.0), stats("alice", 3, 5.0, 30, 1.0), stats("root", 2, 20.0, 20, 9.0)],
+            ..UserStatsStore::default()
+        };
+        let order = |store: &UserStatsStore| store.users.iter().map(|stats| stats.user.clone()).collect::<Vec<_>>();
+
+        store.sort(UserSortParam::User);
+        assert_eq!(order(&store), ["alice", "bob", "root"]);
+        store.sort(UserSortParam::Processes);
+        assert_eq!(order(&store), ["alice", "root", "bob"]);
+        store.sort(UserSortParam::Cpu);
+        assert_eq!(order(&store), ["bob", "root", "alice"]);
+        store.sort(UserSortParam::Mem);
+        assert_eq!(order(&store), ["alice", "root", "bob"]);
+        store.sort(UserSortParam::Io);
+        assert_eq!(order(&store), ["root", "alice", "bob"]);
+    }
+}
@@ -0,0 +1,102 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Alignment, Constraint, Rect};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::style::Color::{Black, Cyan};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Cell, HighlightSpacing, Row, StatefulWidget, Table, TableState};
+use crate::units::{Unit, UnitFormat};
+use crate::user_stats::UserStats;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum UserSortParam {
+    User,
+    Processes,
+    Cpu,
+    Mem,
+    Io,
+}
+
+pub struct UserTableComponent<'a> {
+    users: &'a [UserStats],
+    sort_param: UserSortParam,
+    unit_format: UnitFormat,
+    focused: bool,
+}
+
+impl<'a> UserTableComponent<'a> {
+    pub fn new(users: &'a [UserStats], sort_param: UserSortParam, unit_format: UnitFormat) -> Self {
+        Self {
+            users,
+            sort_param,
+            unit_format,
+            focused: false,
+        }
+    }
+
+    pub fn focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+}
+
+impl StatefulWidget for UserTableComponent<'_> {
+    type State = TableState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+
+        // Columns of every sort key, READ/s and WRITE/s both sort by I/O
+        let sort_columns: &[&str] = match self.sort_param {
+            UserSortParam::User => &["USER"],
+            UserSortParam::Processes => &["PROCS"],
+            UserSortParam::Cpu => &["CPU%"],
+            UserSortParam::Mem => &["MEM", "MEM%"],
+            UserSortParam::Io => &["READ/s", "WRITE/s"],
+        };
+
+        let header = ["USER", "PROCS", "CPU%", "MEM", "MEM%", "READ/s", "WRITE/s"]
+            .into_iter()
+            .map(|i| {
+                if sort_columns.contains(&i) {
+                    Cell::from(Line::from(i).alignment(Alignment::Left).bg(Cyan))
+                } else {
+                    Cell::from(Line::from(i).alignment(Alignment::Left))
+                }
+            })
+            .collect::<Row>()
+            .style(Style::new().fg(Color::Rgb(0, 0, 0)).bg(Color::Green))
+            .height(1);
+
+        let rows = self.users.iter().map(|stats| {
+            Row::new([
+                Cell::from(stats.user.clone()),
+                Cell::from(stats.processes.to_string()),
+                Cell::from(self.unit_format.number(stats.cpu)),
+                Cell::from(self.unit_format.format(Unit::Bytes, stats.mem as f64)),
+                Cell::from(self.unit_format.number(stats.mem_per)),
+                Cell::from(self.unit_format.format(Unit::BytesPerSec, stats.read_rate)),
+                Cell::from(self.unit_format.format(Unit::BytesPerSec, stats.write_rate)),
+            ])
+                .height(1)
+        });
+
+        let t = Table::new(
+            rows,
+            [
+                Constraint::Fill(1),
+                Constraint::Length(6),
+                Constraint::Length(7),
+                Constraint::Length(12),
+                Constraint::Length(6),
+                Constraint::Length(12),
+                Constraint::Length(12),
+            ],
+        )
+            .header(header)
+            .block(Block::bordered().title(" Users ")
+                .border_style(if self.focused { Style::new().fg(Color::Yellow) } else { Style::new() }))
+            .row_highlight_style(Style::new().fg(Black).bg(Cyan))
+            .highlight_spacing(HighlightSpacing::Always);
+
+        t.render(area, buf, state);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::process_object::ProcessObject;
+
+// Glob match with "*" (any sequence) and "?" (any character)
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;     // (pattern position after "*", text position)
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p + 1, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+// Processes pinned to the top of the process table, by name or glob pattern
+#[derive(Default)]
+pub struct WatchList {
+    patterns: Vec<String>,
+}
+
+impl WatchList {
+    pub fn new(patterns: Vec<String>) -> Self {
+        Self {
+            patterns,
+        }
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        self.patterns.iter().any(|pattern| glob_match(pattern, name))
+    }
+
+    // Unpin the name if any pattern matches it, otherwise pin it by exact name
+    pub fn toggle(&mut self, name: &str) {
+        if self.matches(name) {
+            self.patterns.retain(|pattern| !glob_match(pattern, name));
+        } else {
+            self.patterns.push(name.to_string());
+        }
+    }
+
+    // Move watched processes to the top keeping their order, add "not running" rows for patterns without processes
+    pub fn pin(&self, process_object_vec: Vec<ProcessObject>) -> Vec<ProcessObject> {
+        if self.patterns.is_empty() {
+            return process_object_vec;
+        }
+
+        let (mut pinned, other): (Vec<_>, Vec<_>) = process_object_vec.into_iter()
+            .map(|mut p_obj| {
+                p_obj.pinned = self.matches(p_obj.name());
+                p_obj
+            })
+            .partition(|p_obj| p_obj.pinned);
+
+        for pattern in &self.patterns {
+            if !pinned.iter().any(|p_obj| glob_match(pattern, p_obj.name())) {
+                pinned.push(ProcessObject::not_running(pattern));
+            }
+        }
+
+        pinned.extend(other);
+        pinned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_wildcards() {
+        assert!(glob_match("nginx*", "nginx"));
+        assert!(glob_match("nginx*", "nginx-worker"));
+        assert!(glob_match("*sql*", "postgresql"));
+        assert!(glob_match("php?", "php8"));
+        assert!(!glob_match("php?", "php"));
+        assert!(!glob_match("postgres", "postgres-backup"));
+        assert!(glob_match("a*b*c", "aXXbYYbc"));
+        assert!(!glob_match("a*b*c", "aXXbYY"));
+    }
+
+    #[test]
+    fn toggle_unpins_every_matching_pattern() {
+        let mut watch_list = WatchList::new(vec!["nginx*".to_string(), "nginx".to_string(), "postgres".to_string()]);
+        watch_list.toggle("nginx");
+        assert_eq!(watch_list.patterns(), ["postgres"]);
+        watch_list.toggle("redis");
+        assert_eq!(watch_list.patterns(), ["postgres", "redis"]);
+    }
+}